@@ -295,6 +295,22 @@ impl Block {
         false
     }
 
+    /// Return true iff `f` returns true for any key or value in this block, including those in
+    /// sub-blocks.
+    pub fn any_token<F: Fn(&Token) -> bool>(&self, f: &F) -> bool {
+        self.v.iter().any(|item| match item {
+            BlockItem::Value(token) => f(token),
+            BlockItem::Block(block) => block.any_token(f),
+            BlockItem::Field(Field(key, _, bv)) => {
+                f(key)
+                    || match bv {
+                        BV::Value(token) => f(token),
+                        BV::Block(block) => block.any_token(f),
+                    }
+            }
+        })
+    }
+
     /// Return the number of times `name` occurs in this block as a field key.
     #[allow(dead_code)] // Not used by all games
     pub fn count_keys(&self, name: &str) -> usize {
@@ -433,6 +449,32 @@ impl Block {
         self.get_field_at_date(name, date).and_then(BV::get_value)
     }
 
+    /// Just like [`Block::get_field_value_at_date`] but also return the date of the history block
+    /// the value was set in, or `None` if it's the value from the beginning of time.
+    #[allow(dead_code)] // Not used by all games
+    pub fn get_field_value_and_date_at_date(
+        &self,
+        name: &str,
+        date: Date,
+    ) -> Option<(Option<Date>, &Token)> {
+        let mut found_date: Option<Date> = None;
+        let mut found: Option<&Token> = None;
+
+        for Field(key, _, bv) in self.iter_fields() {
+            if key.is(name) && found_date.is_none() {
+                found = bv.get_value();
+            } else if let Ok(isdate) = Date::try_from(key) {
+                if isdate <= date && (found_date.is_none() || found_date.unwrap() < isdate) {
+                    if let Some(value) = bv.get_block().and_then(|b| b.get_field_value(name)) {
+                        found_date = Some(isdate);
+                        found = Some(value);
+                    }
+                }
+            }
+        }
+        found.map(|value| (found_date, value))
+    }
+
     /// Return a sorted vector of macro parameters taken by this block.
     /// Macro parameters are between `$` like `$CHARACTER$`.
     pub fn macro_parms(&self) -> Vec<&'static str> {
@@ -114,6 +114,15 @@ impl Characters {
         self.characters.values().map(|ch| &ch.key).chain(self.duplicates.iter().map(|ch| &ch.key))
     }
 
+    pub fn get(&self, key: &str) -> Option<&Character> {
+        self.characters.get(key)
+    }
+
+    /// Iterate over the characters that are born by the date set in the config, if any.
+    pub fn iter(&self) -> impl Iterator<Item = &Character> {
+        self.characters.values().filter(|ch| ch.born_by(self.config_only_born))
+    }
+
     pub fn is_alive(&self, item: &Token, date: Date) -> bool {
         if let Some(item) = self.characters.get(item.as_str()) {
             item.is_alive(date)
@@ -246,10 +255,6 @@ impl FileHandler<Block> for Characters {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
@@ -289,6 +294,10 @@ impl Character {
         Self { key, block, ancestor_state: AtomicAncestorState::new(AncestorState::Unchecked) }
     }
 
+    pub fn key(&self) -> &Token {
+        &self.key
+    }
+
     pub fn born_by(&self, born_by: Option<Date>) -> bool {
         if let Some(date) = born_by {
             self.block.get_field_at_date("birth", date).is_some()
@@ -325,6 +334,38 @@ impl Character {
             .or_else(|| self.block.get_field_value_at_date("religion", date))
     }
 
+    /// Return the employer of the character on `date`, and the date they were employed.
+    /// The employer is `0` if the character was made unemployed.
+    pub fn employer_at(&self, date: Date) -> Option<(Option<Date>, &Token)> {
+        self.block.get_field_value_and_date_at_date("employer", date)
+    }
+
+    /// Return the marriages and divorces in this character's own history up to `date`, in order.
+    /// Each comes with the date, the spouse, and whether it was a marriage.
+    /// The spouses' own histories can also marry or divorce them, so these alone don't say who
+    /// the character is married to.
+    pub fn spouse_events_at(&self, date: Date) -> Vec<(Date, &Token, bool)> {
+        let mut events = Vec::new();
+        for (key, block) in self.block.iter_definitions() {
+            if let Ok(isdate) = Date::try_from(key) {
+                if isdate <= date {
+                    for (key, value) in block.iter_assignments() {
+                        match key.as_str() {
+                            "add_spouse" | "add_matrilineal_spouse" | "add_same_sex_spouse" => {
+                                events.push((isdate, value, true));
+                            }
+                            "remove_spouse" => events.push((isdate, value, false)),
+                            _ => (),
+                        }
+                    }
+                }
+            }
+        }
+        // A stable sort, to keep the order of events on the same date.
+        events.sort_by_key(|(date, _, _)| *date);
+        events
+    }
+
     fn validate_life_event(
         date: Date,
         gender: Gender,
@@ -87,10 +87,6 @@ impl FileHandler<Block> for Doctrines {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
@@ -47,10 +47,6 @@ impl FileHandler<Block> for CharacterInteractionCategories {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
@@ -78,6 +78,7 @@ pub mod scripted_animations;
 pub mod scripted_costs;
 pub mod scripted_illustrations;
 pub mod secrets;
+pub mod start_dates;
 pub mod stories;
 pub mod struggle;
 pub mod succession;
@@ -42,6 +42,15 @@ impl ProvinceHistories {
         }
     }
 
+    /// Return the faith of the province on `date`, if its history gives it one.
+    pub fn get_faith(&self, provid: ProvId, date: Date) -> Option<&Token> {
+        self.provinces.get(&provid).and_then(|item| {
+            item.block
+                .get_field_value_at_date("faith", date)
+                .or_else(|| item.block.get_field_value_at_date("religion", date))
+        })
+    }
+
     pub fn check_pod_faiths(&self, data: &Everything, titles: &Titles) {
         for bookmark in [
             Date::new(1230, 1, 4),
@@ -79,10 +88,6 @@ impl FileHandler<Block> for ProvinceHistories {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read_detect_encoding(entry, parser)
     }
 
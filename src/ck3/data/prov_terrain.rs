@@ -64,12 +64,12 @@ impl FileHandler<Block> for ProvinceTerrains {
         PathBuf::from("common/province_terrain")
     }
 
-    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with("province_terrain.txt") {
-            // Omit _province_properties.txt
-            return None;
-        }
+    fn handles(&self, entry: &FileEntry) -> bool {
+        // Omit _province_properties.txt
+        entry.filename().to_string_lossy().ends_with("province_terrain.txt")
+    }
 
+    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
         PdxFile::read_detect_encoding(entry, parser)
     }
 
@@ -141,11 +141,12 @@ impl FileHandler<Block> for ProvinceProperties {
         PathBuf::from("common/province_terrain")
     }
 
+    fn handles(&self, entry: &FileEntry) -> bool {
+        // Omit _province_terrain.txt
+        entry.filename().to_string_lossy().ends_with("province_properties.txt")
+    }
+
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with("province_properties.txt") {
-            // Omit _province_terrain.txt
-            return None;
-        }
         PdxFile::read_detect_encoding(entry, parser)
     }
 
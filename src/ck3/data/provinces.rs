@@ -3,12 +3,13 @@ use std::str::FromStr;
 
 use bitvec::bitbox;
 use bitvec::boxed::BitBox;
-use image::{DynamicImage, Rgb};
+use image::{DynamicImage, Rgb, RgbImage};
 
 use crate::block::Block;
+use crate::data::province_map::ProvinceMap;
 use crate::db::{Db, DbKind};
 use crate::everything::Everything;
-use crate::fileset::{FileEntry, FileHandler};
+use crate::fileset::{FileEntry, FileHandler, FileKind};
 use crate::game::GameFlags;
 use crate::helpers::{TigerHashMap, TigerHashSet};
 use crate::item::Item;
@@ -75,6 +76,12 @@ pub struct Ck3Provinces {
     /// Kept and used for error reporting.
     definition_csv: Option<FileEntry>,
 
+    /// Kept and used for error reporting.
+    adjacencies_csv: Option<FileEntry>,
+
+    /// The provinces.png, kept until `finalize` to check the geometry of the map.
+    provinces_png: Option<(FileEntry, RgbImage)>,
+
     adjacencies: Vec<Adjacency>,
 
     impassable: TigerHashSet<ProvId>,
@@ -207,10 +214,60 @@ impl Ck3Provinces {
         self.sea_or_river.contains(&provid)
     }
 
+    /// Whether `default.map` lists the province as sea, river, lake or impassable.
+    pub(crate) fn is_impassable(&self, provid: ProvId) -> bool {
+        self.impassable.contains(&provid)
+    }
+
+    /// Iterate over the provinces that should have a barony, with the token of their id in
+    /// `definition.csv`.
+    pub(crate) fn iter_land(&self) -> impl Iterator<Item = (ProvId, &Token)> {
+        // Province 0 is a placeholder at the start of definition.csv.
+        self.provinces
+            .values()
+            .filter(|p| p.id != 0 && !self.impassable.contains(&p.id))
+            .map(|p| (p.id, &p.key))
+    }
+
     pub fn iter_keys(&self) -> impl Iterator<Item = &Token> {
         self.provinces.values().map(|item| &item.key)
     }
 
+    /// Check the geometry of the map, if the mod changes any of the map files.
+    /// The base game's map is assumed to be fine, and checking a full-size map takes a while.
+    fn check_map(&mut self, ids: &TigerHashMap<Rgb<u8>, ProvId>) {
+        let Some((provinces_png, img)) = self.provinces_png.take() else {
+            return;
+        };
+        let is_mod = |entry: Option<&FileEntry>| entry.is_some_and(|e| e.kind() == FileKind::Mod);
+        if !is_mod(Some(&provinces_png))
+            && !is_mod(self.definition_csv.as_ref())
+            && !is_mod(self.adjacencies_csv.as_ref())
+        {
+            return;
+        }
+
+        let map = ProvinceMap::new(img);
+        map.check_areas(&provinces_png, |color| ids.get(&color).map(|id| format!("province {id}")));
+
+        let mut ids: Vec<ProvId> = ids.values().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            let province = &self.provinces[&id];
+            if map.area(province.color).is_none() {
+                let msg = format!("the color of province {id} does not appear in provinces.png");
+                err(ErrorKey::Colors)
+                    .msg(msg)
+                    .loc(&province.key)
+                    .loc_msg(&provinces_png, "map")
+                    .push();
+            }
+        }
+        for item in &self.adjacencies {
+            item.check_coords(&map, self);
+        }
+    }
+
     pub fn validate(&self, data: &Everything) {
         for item in &self.adjacencies {
             item.validate(self);
@@ -234,6 +291,14 @@ impl FileHandler<FileContent> for Ck3Provinces {
         PathBuf::from("map_data")
     }
 
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.path().components().count() == 2
+            && matches!(
+                &*entry.filename().to_string_lossy(),
+                "adjacencies.csv" | "definition.csv" | "provinces.png" | "default.map"
+            )
+    }
+
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<FileContent> {
         if entry.path().components().count() == 2 {
             match &*entry.filename().to_string_lossy() {
@@ -296,6 +361,7 @@ impl FileHandler<FileContent> for Ck3Provinces {
     fn handle_file(&mut self, entry: &FileEntry, content: FileContent) {
         match content {
             FileContent::Adjacencies(content) => {
+                self.adjacencies_csv = Some(entry.clone());
                 let mut seen_terminator = false;
                 for csv in parse_csv(entry, 1, &content) {
                     if csv[0].is("-1") {
@@ -329,6 +395,7 @@ impl FileHandler<FileContent> for Ck3Provinces {
                                 .commit(true);
                         }
                     }
+                    self.provinces_png = Some((entry.clone(), img));
                 }
             }
             FileContent::DefaultMap(block) => self.load_impassable(&block),
@@ -370,10 +437,10 @@ impl FileHandler<FileContent> for Ck3Provinces {
                 untidy(ErrorKey::Colors).msg(msg).loc(definition_csv).push();
             }
         }
+        self.check_map(&seen_colors);
     }
 }
 
-#[allow(dead_code)] // TODO
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Coords {
     x: i32,
@@ -390,8 +457,7 @@ pub struct Adjacency {
     /// sea or `river_large`
     kind: Token,
     through: ProvId,
-    /// TODO: check start and stop are map coordinates and have the right color on province.png
-    /// They can be -1 -1 though.
+    /// Map coordinates on the `from` and `to` provinces, or -1 -1.
     start: Coords,
     stop: Coords,
     comment: Token,
@@ -447,6 +513,32 @@ impl Adjacency {
             }
         }
     }
+
+    /// Check that the start and stop coordinates are on the `from` and `to` provinces.
+    /// Like other map positions, the y coordinate counts from the bottom of the map.
+    fn check_coords(&self, map: &ProvinceMap, provinces: &Ck3Provinces) {
+        for (coords, provid, which) in
+            [(self.start, self.from, "start"), (self.stop, self.to, "stop")]
+        {
+            if coords.x == -1 && coords.y == -1 {
+                continue;
+            }
+            // Unknown provinces are reported in `validate`.
+            let Some(province) = provinces.provinces.get(&provid) else {
+                continue;
+            };
+            let (x, y) = (i64::from(coords.x), i64::from(coords.y));
+            let msg = match map.color_at(x, y) {
+                None => format!("{which} coordinates ({x}, {y}) are outside the map"),
+                Some(color) if color != province.color => {
+                    format!("{which} coordinates ({x}, {y}) are not on province {provid}")
+                }
+                Some(_) => continue,
+            };
+            let info = "they should be a pixel of the province's color in provinces.png, counting y from the bottom, or -1 -1";
+            warn(ErrorKey::Validation).msg(msg).info(info).loc(self.line).push();
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
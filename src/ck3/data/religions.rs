@@ -201,6 +201,10 @@ pub struct Faith {
 }
 
 impl Faith {
+    pub fn religion(&self) -> &Token {
+        &self.religion
+    }
+
     fn check_have_customs(&self, key: &Token, block: &Block, data: &Everything) {
         let locas = block.get_field_block("localization");
         for loca in CUSTOM_RELIGION_LOCAS {
@@ -0,0 +1,395 @@
+//! Simulation of the world at the start date of each bookmark, from the title, character and
+//! province histories.
+//!
+//! The history entries are validated one by one elsewhere. Here they are put together, to find
+//! combinations of entries that the game can't start with, such as titles held by dead characters
+//! or lieges who are vassals of their own vassals.
+
+use crate::ck3::data::characters::{Character, Gender};
+use crate::ck3::data::religions::Faith;
+use crate::ck3::data::title_history::TitleHistory;
+use crate::ck3::data::titles::Tier;
+use crate::date::Date;
+use crate::everything::Everything;
+use crate::helpers::{TigerHashMap, TigerHashSet};
+use crate::item::Item;
+use crate::report::{err, warn, ErrorKey};
+use crate::token::{Loc, Token};
+
+/// The kinds of problems found, so that each problem is reported only once, for the earliest
+/// start date that has it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+enum Problem {
+    DeadHolder,
+    NoRealm,
+    LiegeCycle,
+    OutranksLiege,
+    Spouses,
+    DeadEmployer,
+    CountyFaith,
+}
+
+type Reported = TigerHashSet<(Loc, Problem)>;
+
+/// A marriage (`true`) or divorce between two characters, from the history of the first.
+type SpouseEvent<'a> = (Date, bool, &'a Character, &'a Character, &'a Token);
+
+/// Check the world as the histories leave it at the start date of each bookmark.
+pub fn validate_start_dates(data: &Everything) {
+    let mut dates: Vec<(Date, &Token)> = Vec::new();
+    for (key, block) in data.database.iter_key_block(Item::Bookmark) {
+        if let Some(date) = block.get_field_date("start_date") {
+            dates.push((date, key));
+        }
+    }
+    for (key, block) in data.database.iter_key_block(Item::BookmarkGroup) {
+        if let Some(date) = block.get_field_date("default_start_date") {
+            dates.push((date, key));
+        }
+    }
+    dates.sort_unstable_by(|(a, a_key), (b, b_key)| a.cmp(b).then(a_key.loc.cmp(&b_key.loc)));
+    dates.dedup_by_key(|(date, _)| *date);
+
+    let mut reported = Reported::default();
+    for (date, bookmark) in dates {
+        World::new(date, bookmark, data, &mut reported).validate(&mut reported);
+    }
+}
+
+/// The titles and lieges of the living title holders on one start date.
+struct World<'a> {
+    date: Date,
+    /// The bookmark or bookmark group that starts on this date.
+    bookmark: &'a Token,
+    data: &'a Everything,
+    /// The living holder of each held title.
+    holders: TigerHashMap<&'static str, &'a Character>,
+    /// The titles held by each living title holder.
+    held: TigerHashMap<&'static str, (&'a Character, Vec<&'a TitleHistory>)>,
+    /// The lieges of each title holder who has them, with the `liege` entries that made it so.
+    /// These come from the holder's primary title, which is their highest tier one. If they have
+    /// several titles of that tier, all of those count, so a holder can end up with more than one
+    /// liege.
+    lieges: TigerHashMap<&'static str, Vec<(&'a Character, &'a Token)>>,
+}
+
+impl<'a> World<'a> {
+    fn new(date: Date, bookmark: &'a Token, data: &'a Everything, reported: &mut Reported) -> Self {
+        let mut world = World {
+            date,
+            bookmark,
+            data,
+            holders: TigerHashMap::default(),
+            held: TigerHashMap::default(),
+            lieges: TigerHashMap::default(),
+        };
+        world.find_holders(reported);
+        world.find_lieges(reported);
+        world
+    }
+
+    /// Describe the start date, for use in report messages.
+    fn when(&self) -> String {
+        format!("on {}, the start date of `{}`", self.date, self.bookmark)
+    }
+
+    fn find_holders(&mut self, reported: &mut Reported) {
+        for history in self.data.title_history.iter() {
+            let Some((since, holder)) = history.holder_at(self.date) else {
+                continue;
+            };
+            if holder.is("0") {
+                continue;
+            }
+            // Unknown characters are reported elsewhere.
+            let Some(ch) = self.data.characters.get(holder.as_str()) else {
+                continue;
+            };
+            if !ch.is_alive(self.date) {
+                // If the holder wasn't alive when they got the title, that's reported elsewhere.
+                if since.map_or(true, |since| ch.is_alive(since))
+                    && reported.insert((holder.loc, Problem::DeadHolder))
+                {
+                    let state = if ch.born_by(Some(self.date)) { "dead" } else { "not born yet" };
+                    let msg = format!(
+                        "{} is held by {holder}, who is {state} {}",
+                        history.key(),
+                        self.when()
+                    );
+                    let info = "the title needs another holder by that date";
+                    err(ErrorKey::History).msg(msg).info(info).loc(holder).push();
+                }
+                continue;
+            }
+            self.holders.insert(history.key().as_str(), ch);
+            self.held.entry(holder.as_str()).or_insert_with(|| (ch, Vec::new())).1.push(history);
+        }
+    }
+
+    /// Return the tier of the highest title the character holds.
+    fn top_tier(&self, ch: &Character) -> Option<Tier> {
+        self.held
+            .get(ch.key().as_str())
+            .and_then(|(_, titles)| titles.iter().map(|history| history.tier()).max())
+    }
+
+    /// The `liege` of a holder's primary title makes them a vassal of the holder of that liege
+    /// title. The lieges of their other titles don't decide whose vassal they are, but must
+    /// still have a holder.
+    fn find_lieges(&mut self, reported: &mut Reported) {
+        for (&id, (ch, titles)) in &self.held {
+            let top_tier = titles.iter().map(|history| history.tier()).max();
+            for history in titles {
+                let Some((_, liege)) = history.liege_at(self.date) else {
+                    continue;
+                };
+                if liege.is("0") {
+                    continue;
+                }
+                if let Some(liege_ch) = self.holders.get(liege.as_str()) {
+                    if liege_ch.key().as_str() != id && Some(history.tier()) == top_tier {
+                        self.lieges.entry(id).or_default().push((liege_ch, liege));
+                    }
+                } else if self.is_vacant(liege) && reported.insert((liege.loc, Problem::NoRealm)) {
+                    let msg = format!(
+                        "{} is a vassal of {liege}, which has no holder {}",
+                        history.key(),
+                        self.when()
+                    );
+                    let info = format!("this leaves {} without a realm", ch.key());
+                    err(ErrorKey::History).msg(msg).info(info).loc(liege).push();
+                }
+            }
+        }
+    }
+
+    /// Whether the title has no holder at all, as opposed to a holder who is dead, which is
+    /// reported separately.
+    fn is_vacant(&self, title: &Token) -> bool {
+        self.data
+            .title_history
+            .get(title.as_str())
+            .and_then(|history| history.holder_at(self.date))
+            .map_or(true, |(_, holder)| holder.is("0"))
+    }
+
+    fn validate(&self, reported: &mut Reported) {
+        self.check_liege_cycles(reported);
+        self.check_liege_tiers(reported);
+        self.check_spouses(reported);
+        self.check_employers(reported);
+        self.check_county_faiths(reported);
+    }
+
+    fn check_liege_cycles(&self, reported: &mut Reported) {
+        let mut ids: Vec<&'static str> = self.lieges.keys().copied().collect();
+        ids.sort_unstable();
+        let mut done = TigerHashSet::default();
+        for id in ids {
+            self.find_liege_cycle(id, &mut Vec::new(), &mut done, reported);
+        }
+    }
+
+    /// Depth-first search for cycles through the lieges of character `id`.
+    /// `path` holds the vassals and `liege` entries that led here.
+    fn find_liege_cycle(
+        &self,
+        id: &'static str,
+        path: &mut Vec<(&'static str, &'a Token)>,
+        done: &mut TigerHashSet<&'static str>,
+        reported: &mut Reported,
+    ) {
+        if done.contains(id) {
+            return;
+        }
+        if let Some(pos) = path.iter().position(|(vassal, _)| *vassal == id) {
+            self.report_liege_cycle(&path[pos..], reported);
+            return;
+        }
+        for (liege, token) in self.lieges.get(id).into_iter().flatten() {
+            path.push((id, token));
+            self.find_liege_cycle(liege.key().as_str(), path, done, reported);
+            path.pop();
+        }
+        done.insert(id);
+    }
+
+    fn report_liege_cycle(&self, cycle: &[(&str, &Token)], reported: &mut Reported) {
+        // Start the cycle at the earliest `liege` entry, so that the report is the same every run.
+        let mut cycle = cycle.to_vec();
+        let first = (0..cycle.len()).min_by_key(|&i| cycle[i].1.loc).unwrap_or(0);
+        cycle.rotate_left(first);
+        if !reported.insert((cycle[0].1.loc, Problem::LiegeCycle)) {
+            return;
+        }
+        let mut ids: Vec<&str> = cycle.iter().map(|(id, _)| *id).collect();
+        ids.push(ids[0]);
+        let msg = format!("vassals and lieges form a cycle {}: {}", self.when(), ids.join(" -> "));
+        let mut report = err(ErrorKey::History).msg(msg).loc(cycle[0].1);
+        for (_, token) in &cycle[1..] {
+            report = report.loc_msg(*token, "next liege");
+        }
+        report.push();
+    }
+
+    fn check_liege_tiers(&self, reported: &mut Reported) {
+        for (id, lieges) in &self.lieges {
+            let Some((ch, _)) = self.held.get(id) else {
+                continue;
+            };
+            for (liege, token) in lieges {
+                let (Some(tier), Some(liege_tier)) = (self.top_tier(ch), self.top_tier(liege))
+                else {
+                    continue;
+                };
+                if tier >= liege_tier && reported.insert((token.loc, Problem::OutranksLiege)) {
+                    let msg = format!(
+                        "{id} holds a {tier} but is a vassal of {}, whose highest title is a {liege_tier} {}",
+                        liege.key(),
+                        self.when()
+                    );
+                    let info = "a vassal's highest title must be lower tier than their liege's";
+                    err(ErrorKey::TitleTier).msg(msg).info(info).loc(*token).push();
+                }
+            }
+        }
+    }
+
+    fn check_spouses(&self, reported: &mut Reported) {
+        // Marriages and divorces can be entered in the history of either spouse, so collect
+        // them per couple before deciding who is still married.
+        let mut couples: TigerHashMap<(&str, &str), Vec<SpouseEvent>> = TigerHashMap::default();
+        for ch in self.data.characters.iter().filter(|ch| ch.is_alive(self.date)) {
+            for (date, token, added) in ch.spouse_events_at(self.date) {
+                let Some(spouse) = self.data.characters.get(token.as_str()) else {
+                    continue;
+                };
+                if !spouse.is_alive(self.date) {
+                    continue;
+                }
+                let (a, b) = (ch.key().as_str(), spouse.key().as_str());
+                let couple = if a < b { (a, b) } else { (b, a) };
+                couples.entry(couple).or_default().push((date, added, ch, spouse, token));
+            }
+        }
+
+        let mut marriages: TigerHashMap<&str, Vec<(&Character, &Token)>> = TigerHashMap::default();
+        for events in couples.values_mut() {
+            // A divorce on the same date as a marriage wins, whichever history it is in.
+            events.sort_by_key(|(date, added, _, _, _)| (*date, !*added));
+            let Some(&(_, true, ch, spouse, token)) = events.last() else {
+                continue;
+            };
+            marriages.entry(ch.key().as_str()).or_default().push((spouse, token));
+            marriages.entry(spouse.key().as_str()).or_default().push((ch, token));
+        }
+
+        for (id, spouses) in &mut marriages {
+            if spouses.len() < 2 {
+                continue;
+            }
+            let Some(ch) = self.data.characters.get(id) else {
+                continue;
+            };
+            let info = if ch.gender() == Gender::Female {
+                "women can only have one spouse"
+            } else if ch
+                .get_faith(self.date)
+                .map_or(true, |faith| allows_polygamy(faith, self.data))
+            {
+                continue;
+            } else {
+                "their faith does not allow more than one spouse"
+            };
+            spouses.sort_unstable_by_key(|(_, token)| token.loc);
+            let (first, first_token) = spouses[0];
+            for (spouse, token) in &spouses[1..] {
+                if reported.insert((token.loc, Problem::Spouses)) {
+                    let msg = format!(
+                        "{id} is married to both {} and {} {}",
+                        first.key(),
+                        spouse.key(),
+                        self.when()
+                    );
+                    warn(ErrorKey::History)
+                        .msg(msg)
+                        .info(info)
+                        .loc(*token)
+                        .loc_msg(first_token, "other marriage")
+                        .push();
+                }
+            }
+        }
+    }
+
+    fn check_employers(&self, reported: &mut Reported) {
+        for ch in self.data.characters.iter().filter(|ch| ch.is_alive(self.date)) {
+            let Some((since, employer)) = ch.employer_at(self.date) else {
+                continue;
+            };
+            if employer.is("0") {
+                continue;
+            }
+            let Some(employer_ch) = self.data.characters.get(employer.as_str()) else {
+                continue;
+            };
+            // If the employer wasn't alive when the character was employed, that's reported
+            // elsewhere.
+            if !employer_ch.is_alive(self.date)
+                && since.map_or(true, |since| employer_ch.is_alive(since))
+                && reported.insert((employer.loc, Problem::DeadEmployer))
+            {
+                let msg = format!(
+                    "the employer of {} is {employer}, who is dead {}",
+                    ch.key(),
+                    self.when()
+                );
+                let info = "the character needs another employer by that date";
+                warn(ErrorKey::History).msg(msg).info(info).loc(employer).push();
+            }
+        }
+    }
+
+    fn check_county_faiths(&self, reported: &mut Reported) {
+        for (county, provid) in self.data.titles.iter_county_capitals() {
+            if !self.holders.contains_key(county) {
+                continue;
+            }
+            let Some(history) = self.data.title_history.get(county) else {
+                continue;
+            };
+            if self.data.province_histories.get_faith(provid, self.date).is_none()
+                && reported.insert((history.key().loc, Problem::CountyFaith))
+            {
+                let msg = format!(
+                    "the capital province {provid} of {county} has no faith {}",
+                    self.when()
+                );
+                let info = "the faith is set with `religion` in the province's history";
+                warn(ErrorKey::History).msg(msg).info(info).loc(history.key()).push();
+            }
+        }
+    }
+}
+
+/// Whether the faith allows a character to have more than one spouse.
+/// Faiths whose marriage doctrine isn't known are assumed to allow it.
+fn allows_polygamy(faith: &Token, data: &Everything) -> bool {
+    let Some((_, block, faith)) = data.database.get_item::<Faith>(Item::Faith, faith.as_str())
+    else {
+        return true;
+    };
+    let religion = data.database.get_key_block(Item::Religion, faith.religion().as_str());
+    // The faith's own doctrines override those of its religion.
+    for block in std::iter::once(block).chain(religion.map(|(_, block)| block)) {
+        for doctrine in block.get_field_values("doctrine") {
+            if doctrine.is("doctrine_polygamy") {
+                return true;
+            }
+            if doctrine.is("doctrine_monogamy") || doctrine.is("doctrine_concubines") {
+                return false;
+            }
+        }
+    }
+    true
+}
@@ -47,6 +47,14 @@ impl TitleHistories {
         self.histories.values().map(|item| &item.key)
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &TitleHistory> {
+        self.histories.values()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&TitleHistory> {
+        self.histories.get(key)
+    }
+
     pub fn validate(&self, data: &Everything) {
         for item in self.histories.values() {
             item.validate(data);
@@ -69,10 +77,6 @@ impl FileHandler<Block> for TitleHistories {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read_detect_encoding(entry, parser)
     }
 
@@ -100,6 +104,33 @@ impl TitleHistory {
         Self { key, block, tier }
     }
 
+    pub fn key(&self) -> &Token {
+        &self.key
+    }
+
+    pub fn tier(&self) -> Tier {
+        self.tier
+    }
+
+    /// Return the holder of the title on `date`, and the date they got it.
+    /// The holder is `0` if the title was vacated.
+    pub fn holder_at(&self, date: Date) -> Option<(Option<Date>, &Token)> {
+        let holder = self.block.get_field_value_and_date_at_date("holder", date);
+        let other = self
+            .block
+            .get_field_value_and_date_at_date("holder_ignore_head_of_faith_requirement", date);
+        match (holder, other) {
+            (Some(holder), Some(other)) => Some(if other.0 > holder.0 { other } else { holder }),
+            _ => holder.or(other),
+        }
+    }
+
+    /// Return the de facto liege title of this title on `date`, and the date it was set.
+    /// The liege is `0` if the title was made independent.
+    pub fn liege_at(&self, date: Date) -> Option<(Option<Date>, &Token)> {
+        self.block.get_field_value_and_date_at_date("liege", date)
+    }
+
     pub fn verify_has_holder(&self, token: &Token, date: Date, data: &Everything) {
         let info = "setting the liege will not have effect here";
 
@@ -137,11 +137,103 @@ impl Titles {
         for item in self.titles.values() {
             item.validate(data);
         }
+        self.validate_map(data);
+    }
+
+    /// Check that the baronies and the provinces of the map match up, and that the capitals of
+    /// titles are within their de jure territory.
+    fn validate_map(&self, data: &Everything) {
+        let mut titles: Vec<&Arc<Title>> = self.titles.values().collect();
+        titles.sort_unstable_by_key(|title| title.key.loc);
+
+        let mut baronies: TigerHashMap<ProvId, Vec<&Title>> = TigerHashMap::default();
+        for title in &titles {
+            if title.tier != Tier::Barony {
+                continue;
+            }
+            let Some(token) = title.block.get_field_value("province") else {
+                continue;
+            };
+            let Ok(provid) = token.as_str().parse::<ProvId>() else {
+                continue;
+            };
+            if data.provinces_ck3.is_impassable(provid) {
+                let msg = format!("barony `{}` is on province {provid}", title.key);
+                let info = "default.map lists that province as sea, river, lake or impassable";
+                err(ErrorKey::Validation).msg(msg).info(info).loc(token).push();
+            }
+            baronies.entry(provid).or_default().push(title);
+        }
+
+        let mut land: Vec<(ProvId, &Token)> = data.provinces_ck3.iter_land().collect();
+        land.sort_unstable_by_key(|(provid, _)| *provid);
+        for (provid, token) in land {
+            match baronies.get(&provid).map(Vec::as_slice) {
+                None => {
+                    let msg = format!("province {provid} has no barony");
+                    let info =
+                        "every land province that is not impassable needs exactly one barony";
+                    warn(ErrorKey::MissingItem).msg(msg).info(info).loc(token).push();
+                }
+                Some([first, others @ ..]) => {
+                    for other in others {
+                        let msg = format!("province {provid} is given to more than one barony");
+                        err(ErrorKey::DuplicateItem)
+                            .msg(msg)
+                            .loc(&other.key)
+                            .loc_msg(&first.key, "other barony")
+                            .push();
+                    }
+                }
+                Some([]) => (),
+            }
+        }
+
+        for title in &titles {
+            let Some(capital) = title.block.get_field_value("capital") else {
+                continue;
+            };
+            // Titular titles have no territory, so their capital can be anywhere.
+            if !title.block.iter_definitions().any(|(key, _)| Tier::try_from(key).is_ok()) {
+                continue;
+            }
+            let Some(county) = self.titles.get(capital.as_str()) else {
+                continue;
+            };
+            if county.tier != Tier::County {
+                continue;
+            }
+            let mut parent = county.parent;
+            while let Some(key) = parent {
+                if key == title.key.as_str() {
+                    break;
+                }
+                parent = self.titles.get(key).and_then(|t| t.parent);
+            }
+            if parent.is_none() {
+                let msg = format!(
+                    "capital `{capital}` is not in the de jure territory of `{}`",
+                    title.key
+                );
+                err(ErrorKey::Validation)
+                    .msg(msg)
+                    .loc(capital)
+                    .loc_msg(&county.key, "county")
+                    .push();
+            }
+        }
     }
 
     pub fn capital_of(&self, prov: ProvId) -> Option<&str> {
         self.baronies.get(&prov).and_then(|b| b.capital_of())
     }
+
+    /// Iterate over the counties and the provinces of their capital baronies.
+    pub fn iter_county_capitals(&self) -> impl Iterator<Item = (&str, ProvId)> {
+        self.baronies
+            .iter()
+            .filter_map(|(provid, b)| b.capital_of().map(|county| (county, *provid)))
+    }
 }
 
 impl FileHandler<Block> for Titles {
@@ -150,10 +242,6 @@ impl FileHandler<Block> for Titles {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
@@ -238,7 +326,6 @@ impl Title {
         vd.field_list_items("female_names", Item::Localization);
 
         if Tier::try_from(&self.key) == Ok(Tier::Barony) {
-            // TODO: check that no two baronies have the same province
             vd.field_item("province", Item::Province);
         }
 
@@ -5,7 +5,7 @@ use crate::ck3::tables::misc::{LEGEND_QUALITY, OUTBREAK_INTENSITIES};
 use crate::effect::Effect;
 use crate::effect_validation::*;
 use crate::everything::Everything;
-use crate::helpers::TigerHashMap;
+use crate::helpers::{similar_names, TigerHashMap};
 use crate::item::Item;
 use crate::scopes::Scopes;
 use crate::token::Token;
@@ -42,6 +42,12 @@ pub fn scope_effect(name: &Token, data: &Everything) -> Option<(Scopes, Effect)>
     std::option::Option::None
 }
 
+/// Return the names of the effects that are spelled similarly to `name`.
+pub fn similar_effects(name: &str) -> Vec<&'static str> {
+    let effects = SCOPE_EFFECT.iter().filter(|(_, _, effect)| !matches!(effect, Removed(_, _)));
+    similar_names(name, effects.map(|&(_, effect, _)| effect))
+}
+
 /// A hashed version of [`SCOPE_EFFECT`], for quick lookup by effect name.
 static SCOPE_EFFECT_MAP: Lazy<TigerHashMap<&'static str, (Scopes, Effect)>> = Lazy::new(|| {
     let mut hash = TigerHashMap::default();
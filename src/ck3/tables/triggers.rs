@@ -4,7 +4,7 @@ use crate::ck3::tables::misc::{
     AGENT_SLOT_CONTRIBUTION_TYPE, GOVERNMENT_RULES, LEGEND_QUALITY, OUTBREAK_INTENSITIES,
 };
 use crate::everything::Everything;
-use crate::helpers::TigerHashMap;
+use crate::helpers::{similar_names, TigerHashMap};
 use crate::item::Item;
 use crate::report::{err, ErrorKey};
 use crate::scopes::{ArgumentValue, Scopes};
@@ -68,6 +68,12 @@ pub fn scope_trigger(name: &Token, data: &Everything) -> Option<(Scopes, Trigger
     std::option::Option::None
 }
 
+/// Return the names of the triggers that are spelled similarly to `name`.
+pub fn similar_triggers(name: &str) -> Vec<&'static str> {
+    let triggers = TRIGGER.iter().filter(|(_, _, trigger)| !matches!(trigger, Removed(_, _)));
+    similar_names(name, triggers.map(|&(_, trigger, _)| trigger))
+}
+
 static TRIGGER_MAP: Lazy<TigerHashMap<&'static str, (Scopes, Trigger)>> = Lazy::new(|| {
     let mut hash = TigerHashMap::default();
     for (from, s, trigger) in TRIGGER.iter().copied() {
@@ -184,7 +184,7 @@ pub fn validate_compare_modifier(block: &Block, data: &Everything, sc: &mut Scop
     sc.open_builder();
     let mut valid_target = false;
     vd.field_validated_value("target", |_, mut vd| {
-        valid_target = validate_scope_chain(vd.value(), data, sc, false);
+        valid_target = validate_scope_chain(vd.value(), data, sc, false, false);
         vd.accept();
     });
     sc.finalize_builder();
@@ -10,9 +10,10 @@ use strum::IntoEnumIterator;
 use crate::block::{Block, BlockItem, Comparator, Eq::*, Field, BV};
 use crate::helpers::stringify_list;
 use crate::report::{
-    err, set_predicate, set_show_loaded_mods, set_show_vanilla, Confidence, ErrorKey, ErrorLoc,
-    FilterRule, PointedMessage, Severity,
+    err, set_predicate, set_severity_overrides, set_show_loaded_mods, set_show_vanilla, Confidence,
+    ErrorKey, ErrorLoc, FilterRule, PointedMessage, Severity, SeverityOverride, SeverityOverrides,
 };
+use crate::token::Token;
 
 /// Checks for legacy ignore blocks (that no longer work) and report an error if they are present.
 pub fn check_for_legacy_ignore(config: &Block) {
@@ -76,6 +77,104 @@ pub fn load_filter(config: &Block) {
     }
 }
 
+/// Load the `severity_overrides` block, which changes the severity and optionally the confidence
+/// of reports with a given key, optionally only in certain files.
+///
+/// Each entry is either `key = severity` or `key = { severity = ... confidence = ... file = ... }`.
+pub fn load_severity_overrides(config: &Block) {
+    assert_one_key("severity_overrides", config);
+    let mut overrides = SeverityOverrides::default();
+    if let Some(block) = config.get_field_block("severity_overrides") {
+        for item in block.iter_items() {
+            let Some(Field(key, _, bv)) = item.expect_field() else {
+                continue;
+            };
+            let Ok(error_key) = key.as_str().parse() else {
+                err(ErrorKey::Config).msg(
+                    "Invalid key. In the output, keys are listed between parentheses on the first line of each report. For example, in `Warning(missing-item)`, the key is `missing-item`.",
+                ).loc(key).push();
+                continue;
+            };
+            if let Some(o) = load_severity_override(error_key, bv) {
+                overrides.overrides.push(o);
+            }
+        }
+    }
+    set_severity_overrides(overrides);
+}
+
+fn load_severity_override(key: ErrorKey, bv: &BV) -> Option<SeverityOverride> {
+    match bv {
+        BV::Value(token) => {
+            let severity = parse_severity(token)?;
+            Some(SeverityOverride {
+                key,
+                files: Vec::new(),
+                severity: Some(severity),
+                confidence: None,
+            })
+        }
+        BV::Block(block) => {
+            assert_one_key("severity", block);
+            assert_one_key("confidence", block);
+            let mut o =
+                SeverityOverride { key, files: Vec::new(), severity: None, confidence: None };
+            for item in block.iter_items() {
+                let Some(Field(field, _, bv)) = item.expect_field() else {
+                    continue;
+                };
+                let Some(token) = bv.expect_value() else {
+                    continue;
+                };
+                match field.as_str() {
+                    "severity" => o.severity = Some(parse_severity(token)?),
+                    "confidence" => o.confidence = Some(parse_confidence(token)?),
+                    "file" => o.files.push(token.to_string()),
+                    _ => {
+                        let msg =
+                            "Unexpected key. Valid keys are `severity`, `confidence`, and `file`.";
+                        err(ErrorKey::Config).msg(msg).loc(field).push();
+                    }
+                }
+            }
+            if o.severity.is_none() && o.confidence.is_none() {
+                let msg = "This override changes nothing. Add a `severity` or `confidence`.";
+                err(ErrorKey::Config).msg(msg).loc(block).push();
+                return None;
+            }
+            Some(o)
+        }
+    }
+}
+
+fn parse_severity(token: &Token) -> Option<Severity> {
+    let severity = token.as_str().to_ascii_lowercase().parse().ok();
+    if severity.is_none() {
+        err(ErrorKey::Config)
+            .msg(format!(
+                "Invalid Severity value. Valid values: {}",
+                stringify_list(&Severity::iter().map(Severity::into).collect::<Vec<_>>()),
+            ))
+            .loc(token)
+            .push();
+    }
+    severity
+}
+
+fn parse_confidence(token: &Token) -> Option<Confidence> {
+    let confidence = token.as_str().to_ascii_lowercase().parse().ok();
+    if confidence.is_none() {
+        err(ErrorKey::Config)
+            .msg(format!(
+                "Invalid Confidence value. Valid values are {}",
+                stringify_list(&Confidence::iter().map(Confidence::into).collect::<Vec<_>>())
+            ))
+            .loc(token)
+            .push();
+    }
+    confidence
+}
+
 /// Load a vector of rules from the given block.
 fn load_rules(block: &Block) -> Vec<FilterRule> {
     block.iter_items().filter_map(BlockItem::expect_field).filter_map(load_rule).collect()
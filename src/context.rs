@@ -3,8 +3,8 @@
 use std::borrow::Cow;
 
 use crate::game::Game;
-use crate::helpers::{stringify_choices, ActionOrEvent, TigerHashMap};
-use crate::report::{err, warn, ErrorKey, ReportBuilderStage3};
+use crate::helpers::{similar_names, stringify_choices, ActionOrEvent, TigerHashMap};
+use crate::report::{err, warn, will_maybe_log, ErrorKey, ReportBuilderStage3};
 use crate::scopes::Scopes;
 use crate::token::Token;
 
@@ -230,6 +230,12 @@ impl ScopeContext {
         self.source = source.into();
     }
 
+    /// Return the key of the event or action that is being validated with this context, or if
+    /// there is none, the key of the item that created the context.
+    pub(crate) fn caller(&self) -> &Token {
+        self.traceback.last().map_or(&self.source, ActionOrEvent::token)
+    }
+
     /// Helper function for `root_for_event` and `root_for_action`.
     fn root_for(&self, trace: ActionOrEvent) -> Option<Self> {
         if !self.strict_scopes || self.no_warn || self.traceback.contains(&trace) {
@@ -540,7 +546,16 @@ impl ScopeContext {
                         let info = format!("available names are {}", stringify_choices(&names));
                         builder = builder.info(info);
                     }
-                    self.log_traceback(builder.loc(token)).push();
+                    let mut builder = self.log_traceback(builder.loc(token));
+                    if let Some(prefix) = token.as_str().strip_suffix(name) {
+                        if will_maybe_log(token, ErrorKey::StrictScopes) {
+                            let names = self.names.keys().map(String::as_str);
+                            for similar in similar_names(name, names) {
+                                builder = builder.suggest(token, format!("{prefix}{similar}"));
+                            }
+                        }
+                    }
+                    builder.push();
                 }
                 // Don't treat it as an input scope, because we already warned about it
                 self.is_input.push(None);
@@ -108,15 +108,16 @@ impl FileHandler<Option<Block>> for Assets {
     }
 
     /// TODO: should probably simplify this `FileHandler` by keeping the textures in a separate `FileHandler`.
-    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Option<Block>> {
+    fn handles(&self, entry: &FileEntry) -> bool {
         let name = entry.filename().to_string_lossy();
+        name.ends_with(".dds") || name.ends_with(".asset")
+    }
 
-        if name.ends_with(".dds") {
+    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Option<Block>> {
+        if entry.filename().to_string_lossy().ends_with(".dds") {
             Some(None)
-        } else if name.ends_with(".asset") {
-            PdxFile::read_optional_bom(entry, parser).map(Some)
         } else {
-            None
+            PdxFile::read_optional_bom(entry, parser).map(Some)
         }
     }
 
@@ -92,10 +92,6 @@ impl FileHandler<Block> for Coas {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read_optional_bom(entry, parser)
     }
 
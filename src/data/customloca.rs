@@ -123,9 +123,9 @@ impl CustomLocalization {
                 if let Some(key) = block.get_field_value("localization_key") {
                     if let Some(token) = suffix_token {
                         let loca = format!("{key}{suffix_str}");
-                        data.localization.verify_exists_implied_lang(&loca, token, lang);
+                        data.verify_exists_implied_lang(&loca, token, lang);
                     } else {
-                        data.localization.verify_exists_lang(key, lang);
+                        data.verify_exists_lang(key, lang);
                     }
                 }
             }
@@ -2,6 +2,7 @@
 
 use std::mem::drop;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
 use std::sync::{Arc, RwLock};
 
 use crate::block::{Block, BlockItem, Field, BV};
@@ -198,6 +199,10 @@ impl Gui {
         self.templates.values().map(|item| &item.key)
     }
 
+    pub fn template_used(&self, key: &str) -> bool {
+        self.templates.get(key).is_some_and(|item| item.used.load(Relaxed))
+    }
+
     pub fn type_exists(&self, key: &Lowercase) -> bool {
         self.types.contains_key(key.as_str()) || BuiltinWidget::builtin_current_game(key).is_some()
     }
@@ -206,6 +211,10 @@ impl Gui {
         self.types.values().map(|item| &item.key)
     }
 
+    pub fn type_used(&self, key: &str) -> bool {
+        self.types.get(Lowercase::new(key).as_str()).is_some_and(|item| item.used.load(Relaxed))
+    }
+
     pub fn layer_exists(&self, key: &str) -> bool {
         self.layers.contains_key(key)
     }
@@ -272,11 +281,11 @@ impl FileHandler<Block> for Gui {
         PathBuf::from("gui")
     }
 
-    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".gui") {
-            return None;
-        }
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.filename().to_string_lossy().ends_with(".gui")
+    }
 
+    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
         PdxFile::read_optional_bom(entry, parser)
     }
 
@@ -487,15 +496,20 @@ impl TextFormat {
 
 #[derive(Debug)]
 pub struct GuiTemplate {
-    #[allow(dead_code)] // key is not used, but don't want to remove the code for storing it
     key: Token,
     block: Block,
     gui_block: RwLock<Option<Arc<GuiBlock>>>,
+    /// Whether any gui block uses this template.
+    used: AtomicBool,
 }
 
 impl GuiTemplate {
     pub fn new(key: Token, block: Block) -> Self {
-        Self { key, block, gui_block: RwLock::new(None) }
+        Self { key, block, gui_block: RwLock::new(None), used: AtomicBool::new(false) }
+    }
+
+    pub fn mark_used(&self) {
+        self.used.store(true, Relaxed);
     }
 
     pub fn validate(&self, data: &Everything) {
@@ -553,6 +567,8 @@ pub struct GuiType {
     #[allow(clippy::option_option)] // TODO
     builtin: RwLock<Option<Option<BuiltinWidget>>>,
     gui_block: RwLock<Option<Arc<GuiBlock>>>,
+    /// Whether any gui block is based on this type.
+    used: AtomicBool,
 }
 
 impl GuiType {
@@ -568,9 +584,14 @@ impl GuiType {
             is_builtin_wrapper,
             builtin: RwLock::new(builtin),
             gui_block: RwLock::new(None),
+            used: AtomicBool::new(false),
         }
     }
 
+    pub fn mark_used(&self) {
+        self.used.store(true, Relaxed);
+    }
+
     pub fn validate(&self, data: &Everything) {
         data.verify_exists(Item::GuiType, &self.base);
         let base_lc = Lowercase::new(self.base.as_str());
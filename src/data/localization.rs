@@ -3,7 +3,7 @@
 use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::ffi::OsStr;
-use std::fs::read_to_string;
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::Relaxed;
@@ -14,9 +14,9 @@ use crate::block::Block;
 #[cfg(feature = "ck3")]
 use crate::ck3::tables::localization::{BUILTIN_MACROS_CK3, COMPLEX_TOOLTIPS_CK3};
 use crate::context::ScopeContext;
-use crate::datatype::{validate_datatypes, CodeChain, Datatype};
+use crate::datatype::{validate_datatypes, CodeArg, CodeChain, Datatype};
 use crate::everything::Everything;
-use crate::fileset::{FileEntry, FileHandler, FileKind};
+use crate::fileset::{read_file_to_string, FileEntry, FileHandler, FileKind};
 use crate::game::Game;
 use crate::helpers::{dup_error, stringify_list, TigerHashMap};
 #[cfg(feature = "imperator")]
@@ -26,7 +26,8 @@ use crate::macros::{MacroMapIndex, MACRO_MAP};
 use crate::parse::localization::{parse_loca, ValueParser};
 use crate::parse::ParserMemory;
 use crate::report::{
-    err, report, warn, warn_abbreviated, warn_header, will_maybe_log, ErrorKey, Severity,
+    err, report, warn, warn_abbreviated, warn_header, will_maybe_log, Confidence, ErrorKey,
+    Severity,
 };
 use crate::scopes::Scopes;
 use crate::token::Token;
@@ -46,6 +47,9 @@ pub struct Localization {
     locas: TigerHashMap<&'static str, TigerHashMap<String, LocaEntry>>,
 }
 
+/// The language that translations are compared against.
+pub const REFERENCE_LANGUAGE: &str = "english";
+
 /// List of languages that are supported by the game engine.
 // LAST UPDATED CK3 VERSION 1.14.0.2
 // LAST UPDATED VIC3 VERSION 1.7.6
@@ -95,6 +99,9 @@ pub struct LocaEntry {
     used: AtomicBool,
     /// Whether this entry has been validated with a `ScopeContext`
     validated: AtomicBool,
+    /// The number after the `:` in `key:0 "value"`. It's meant to be increased when the text
+    /// changes, so that translations with a lower number are known to be out of date.
+    version: u32,
 }
 
 impl PartialEq for LocaEntry {
@@ -119,7 +126,32 @@ impl Ord for LocaEntry {
 
 impl LocaEntry {
     pub fn new(key: Token, value: LocaValue, orig: Option<Token>) -> Self {
-        Self { key, value, orig, used: AtomicBool::new(false), validated: AtomicBool::new(false) }
+        Self {
+            key,
+            value,
+            orig,
+            used: AtomicBool::new(false),
+            validated: AtomicBool::new(false),
+            version: 0,
+        }
+    }
+
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub(crate) fn key(&self) -> &Token {
+        &self.key
+    }
+
+    /// The unparsed value, if it could be parsed at all.
+    pub(crate) fn orig(&self) -> Option<&Token> {
+        self.orig.as_ref()
+    }
+
+    pub(crate) fn version(&self) -> u32 {
+        self.version
     }
 
     // returns false to abort expansion in case of an error
@@ -192,7 +224,8 @@ pub enum LocaValue {
     Concat(Vec<LocaValue>),
     #[allow(dead_code)] // the Token is only used for ck3
     Text(Token),
-    Markup,
+    /// The markup text, such as `#bold` or `#color:{1,0,0}`.
+    Markup(Token),
     MarkupEnd,
     Tooltip(Token),
     // Tag, key, value. Tag can influence how tooltip is looked up. If tag is `GAME_TRAIT`,
@@ -227,6 +260,19 @@ fn get_file_lang(filename: &OsStr) -> Option<&'static str> {
 }
 
 impl Localization {
+    /// The languages that are being checked, according to the config file.
+    pub(crate) fn check_langs(&self) -> &[&'static str] {
+        &self.check_langs
+    }
+
+    pub(crate) fn get_lang(&self, key: &str, lang: &'static str) -> Option<&LocaEntry> {
+        self.locas.get(lang)?.get(key)
+    }
+
+    pub(crate) fn iter_lang(&self, lang: &'static str) -> impl Iterator<Item = &LocaEntry> {
+        self.locas.get(lang).into_iter().flat_map(TigerHashMap::values)
+    }
+
     pub fn exists(&self, key: &str) -> bool {
         for lang in &self.mod_langs {
             let hash = self.locas.get(lang);
@@ -320,10 +366,6 @@ impl Localization {
         true
     }
 
-    pub fn verify_exists_lang(&self, token: &Token, lang: &'static str) {
-        self.verify_exists_implied_lang(token.as_str(), token, lang);
-    }
-
     pub fn verify_exists_implied_lang(&self, key: &str, token: &Token, lang: &'static str) {
         if key.is_empty() {
             return;
@@ -394,7 +436,7 @@ impl Localization {
             }
             LocaValue::Tooltip(token) => {
                 // TODO: should this be validated with validate_localization_sc ? (remember to avoid infinite loops)
-                data.localization.verify_exists_lang(token, lang);
+                data.verify_exists_lang(token, lang);
             }
             #[allow(unused_variables)] // tag only used by ck3
             LocaValue::ComplexTooltip(tag, token) => {
@@ -404,7 +446,7 @@ impl Localization {
                     match COMPLEX_TOOLTIPS_CK3.get(&*tag.as_str().to_lowercase()).copied() {
                         None => {
                             // TODO: should this be validated with validate_localization_sc ? (remember to avoid infinite loops)
-                            data.localization.verify_exists_lang(token, lang);
+                            data.verify_exists_lang(token, lang);
                         }
                         Some(None) => (), // token is a runtime id
                         Some(Some(itype)) => data.verify_exists(itype, token),
@@ -412,7 +454,7 @@ impl Localization {
                 }
                 #[cfg(feature = "vic3")]
                 if Game::is_vic3() && !token.starts_with("[") && !is_builtin_macro(token) {
-                    data.localization.verify_exists_lang(token, lang);
+                    data.verify_exists_lang(token, lang);
                 }
                 // TODO: - imperator -
             }
@@ -518,6 +560,27 @@ impl Localization {
         });
     }
 
+    /// Compare each of the mod's translations with the reference language text, and report the
+    /// `$macros$`, `[code]`, and `#markup` that one has and the other doesn't.
+    pub fn check_translations(&self) {
+        let Some(reference) = self.locas.get(REFERENCE_LANGUAGE) else {
+            return;
+        };
+        for (&lang, hash) in &self.locas {
+            if lang == REFERENCE_LANGUAGE {
+                continue;
+            }
+            let mut entries: Vec<&LocaEntry> =
+                hash.values().filter(|entry| entry.key.loc.kind == FileKind::Mod).collect();
+            entries.sort_unstable();
+            for entry in entries {
+                if let Some(orig) = reference.get(entry.key.as_str()) {
+                    compare_translation(entry, orig, lang);
+                }
+            }
+        }
+    }
+
     pub fn mark_category_used(&self, prefix: &str) {
         let mut i = 0;
         loop {
@@ -610,6 +673,10 @@ impl FileHandler<(&'static str, Vec<LocaEntry>)> for Localization {
         PathBuf::from("localization")
     }
 
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.filename().to_string_lossy().ends_with(".yml")
+    }
+
     fn load_file(
         &self,
         entry: &FileEntry,
@@ -618,9 +685,6 @@ impl FileHandler<(&'static str, Vec<LocaEntry>)> for Localization {
         let depth = entry.path().components().count();
         assert!(depth >= 2);
         assert!(entry.path().starts_with("localization"));
-        if !entry.filename().to_string_lossy().ends_with(".yml") {
-            return None;
-        }
 
         // unwrap is safe here because we're only handed files under localization/
         // to_string_lossy is ok because we compare lang against a set of known strings.
@@ -643,14 +707,19 @@ impl FileHandler<(&'static str, Vec<LocaEntry>)> for Localization {
                 let info = "A localization file should be in a subdirectory corresponding to its language.";
                 warn(ErrorKey::Filename).msg(msg).info(info).loc(entry).push();
             }
-            match read_to_string(entry.fullpath()) {
+            match read_file_to_string(entry.fullpath()) {
                 Ok(content) => {
                     return Some((filelang, parse_loca(entry, content, filelang).collect()));
                 }
                 Err(e) => {
                     let msg = "could not read file";
                     let info = &format!("{e:#}");
-                    err(ErrorKey::ReadError).msg(msg).info(info).loc(entry).push();
+                    let builder = err(ErrorKey::ReadError).msg(msg).info(info).loc(entry);
+                    if e.kind() == ErrorKind::InvalidData {
+                        builder.fix_encoding().push();
+                    } else {
+                        builder.push();
+                    }
                 }
             }
         } else if entry.kind() >= FileKind::Vanilla {
@@ -702,6 +771,146 @@ impl Default for Localization {
     }
 }
 
+/// The parts of a localization text that should be the same in every language.
+#[derive(Debug, Default)]
+struct LocaParts {
+    macros: Vec<String>,
+    code: Vec<String>,
+    markup: Vec<String>,
+    /// Texts with macros are only parsed after the macros are filled in, so their code and
+    /// markup are not known.
+    has_macros: bool,
+}
+
+impl LocaParts {
+    fn new(value: &LocaValue) -> Self {
+        let mut parts = LocaParts::default();
+        parts.collect(value);
+        parts.macros.sort_unstable();
+        parts.macros.dedup();
+        parts.code.sort_unstable();
+        parts.markup.sort_unstable();
+        parts
+    }
+
+    fn collect(&mut self, value: &LocaValue) {
+        match value {
+            LocaValue::Macro(v) => {
+                self.has_macros = true;
+                for macrovalue in v {
+                    if let MacroValue::Keyword(keyword) = macrovalue {
+                        self.macros.push(format!("${keyword}$"));
+                    }
+                }
+            }
+            LocaValue::Concat(v) => {
+                for value in v {
+                    self.collect(value);
+                }
+            }
+            LocaValue::Code(chain, _) => self.code.push(format!("[{}]", chain_signature(chain))),
+            LocaValue::Markup(token) => self.markup.push(token.to_string()),
+            LocaValue::MarkupEnd => self.markup.push("#!".to_owned()),
+            _ => (),
+        }
+    }
+}
+
+/// Render a code chain for comparison between languages. Literal arguments are left out
+/// because they are often text that should be translated.
+fn chain_signature(chain: &CodeChain) -> String {
+    let codes: Vec<String> = chain
+        .codes
+        .iter()
+        .map(|code| {
+            if code.arguments.is_empty() {
+                code.name.to_string()
+            } else {
+                let args: Vec<String> = code
+                    .arguments
+                    .iter()
+                    .map(|arg| match arg {
+                        CodeArg::Chain(chain) => chain_signature(chain),
+                        CodeArg::Literal(_) => "'...'".to_owned(),
+                    })
+                    .collect();
+                format!("{}({})", code.name, args.join(","))
+            }
+        })
+        .collect();
+    codes.join(".")
+}
+
+/// Return the items of `a` that are not in `b`, counting duplicates.
+fn missing_from<'a>(a: &'a [String], b: &[String]) -> Vec<&'a str> {
+    let mut b: Vec<&String> = b.iter().collect();
+    let mut missing = Vec::new();
+    for item in a {
+        if let Some(i) = b.iter().position(|other| *other == item) {
+            b.swap_remove(i);
+        } else {
+            missing.push(item.as_str());
+        }
+    }
+    missing
+}
+
+fn compare_translation(entry: &LocaEntry, orig: &LocaEntry, lang: &str) {
+    if matches!(entry.value, LocaValue::Error) || matches!(orig.value, LocaValue::Error) {
+        return;
+    }
+    let parts = LocaParts::new(&entry.value);
+    let orig_parts = LocaParts::new(&orig.value);
+    let (ours, theirs) = (&parts.macros, &orig_parts.macros);
+    report_differences("macros", entry, orig, lang, ours, theirs, Confidence::Reasonable);
+    if !parts.has_macros && !orig_parts.has_macros {
+        // Translators sometimes have good reasons to word things differently, for example to
+        // inflect a name or to emphasize another word, so these are less certain.
+        let (ours, theirs) = (&parts.code, &orig_parts.code);
+        report_differences("code", entry, orig, lang, ours, theirs, Confidence::Weak);
+        let (ours, theirs) = (&parts.markup, &orig_parts.markup);
+        report_differences("markup", entry, orig, lang, ours, theirs, Confidence::Weak);
+    }
+}
+
+fn report_differences(
+    what: &str,
+    entry: &LocaEntry,
+    orig: &LocaEntry,
+    lang: &str,
+    ours: &[String],
+    theirs: &[String],
+    confidence: Confidence,
+) {
+    let key = &entry.key;
+    let missing = missing_from(theirs, ours);
+    if !missing.is_empty() {
+        let msg = format!(
+            "{lang} localization for `{key}` lacks {what} {} from the {REFERENCE_LANGUAGE} text",
+            missing.join(" ")
+        );
+        warn(ErrorKey::Localization)
+            .conf(confidence)
+            .msg(msg)
+            .loc(key)
+            .loc_msg(&orig.key, format!("{REFERENCE_LANGUAGE} text"))
+            .push();
+    }
+    let extra = missing_from(ours, theirs);
+    if !extra.is_empty() {
+        let msg = format!(
+            "{lang} localization for `{key}` has {what} {} that the {REFERENCE_LANGUAGE} text does not have",
+            extra.join(" ")
+        );
+        warn(ErrorKey::Localization)
+            .conf(confidence)
+            .msg(msg)
+            .loc(key)
+            .loc_msg(&orig.key, format!("{REFERENCE_LANGUAGE} text"))
+            .push();
+    }
+}
+
 /// It's been tested that localization/replace/english and localization/english/replace both work
 fn is_replace_path(path: &Path) -> bool {
     for element in path {
@@ -740,6 +949,7 @@ fn normal_capitalization_for_name(name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::token::Loc;
 
     #[test]
     fn test_only_latin_script() {
@@ -761,4 +971,55 @@ mod tests {
         assert!(normal_capitalization_for_name("Abu Abdallah Muhammad"));
         assert!(!normal_capitalization_for_name("AbuAbdallahMuhammad"));
     }
+
+    #[test]
+    fn test_missing_from() {
+        let a = vec!["#!".to_owned(), "#!".to_owned(), "#bold".to_owned()];
+        let b = vec!["#!".to_owned(), "#italic".to_owned()];
+        assert_eq!(missing_from(&a, &b), vec!["#!", "#bold"]);
+        assert_eq!(missing_from(&b, &a), vec!["#italic"]);
+        assert!(missing_from(&a, &a).is_empty());
+    }
+
+    fn test_loc() -> Loc {
+        Loc::for_file(PathBuf::from("test.yml"), FileKind::Mod, PathBuf::from("test.yml"))
+    }
+
+    fn loca_parts(text: &'static str) -> LocaParts {
+        let token = Token::from_static_str(text, test_loc());
+        LocaParts::new(&ValueParser::new(vec![&token]).parse())
+    }
+
+    #[test]
+    fn test_loca_parts() {
+        let parts = loca_parts("#bold [ROOT.Char.GetName]#! has [Custom('x')] and [GetName]");
+        assert!(!parts.has_macros);
+        assert_eq!(parts.code, vec!["[Custom('...')]", "[GetName]", "[ROOT.Char.GetName]"]);
+        assert_eq!(parts.markup, vec!["#!", "#bold"]);
+        assert!(parts.macros.is_empty());
+
+        // Macros are split out when the file is read, before the text is parsed.
+        let keyword = |s| MacroValue::Keyword(Token::from_static_str(s, test_loc()));
+        let text = MacroValue::Text(Token::from_static_str(" and ", test_loc()));
+        let parts = LocaParts::new(&LocaValue::Macro(vec![
+            keyword("KEY"),
+            text,
+            keyword("OTHER"),
+            keyword("KEY"),
+        ]));
+        assert!(parts.has_macros);
+        assert_eq!(parts.macros, vec!["$KEY$", "$OTHER$"]);
+    }
+
+    #[test]
+    fn test_chain_signature() {
+        // The literal arguments are left out, since they are often translated.
+        let ours = loca_parts("[Select_CString(ROOT.Char.IsAI, 'oui', 'non')]");
+        let theirs = loca_parts("[Select_CString(ROOT.Char.IsAI, 'yes', 'no')]");
+        assert_eq!(ours.code, vec!["[Select_CString(ROOT.Char.IsAI,'...','...')]"]);
+        assert_eq!(ours.code, theirs.code);
+
+        let other = loca_parts("[Select_CString(ROOT.Char.IsFemale, 'yes', 'no')]");
+        assert_ne!(ours.code, other.code);
+    }
 }
@@ -23,6 +23,7 @@ pub mod localization;
 pub mod music;
 pub mod on_actions;
 pub mod portrait;
+pub mod province_map;
 pub mod script_values;
 pub mod scripted_effects;
 pub mod scripted_guis;
@@ -77,14 +77,12 @@ impl FileHandler<Block> for Musics {
         PathBuf::from("music")
     }
 
-    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if entry.path().parent().unwrap().ends_with("music_player_categories") {
-            return None;
-        }
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
+    fn handles(&self, entry: &FileEntry) -> bool {
+        !entry.path().parent().unwrap().ends_with("music_player_categories")
+            && entry.filename().to_string_lossy().ends_with(".txt")
+    }
 
+    fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
         PdxFile::read(entry, parser)
     }
 
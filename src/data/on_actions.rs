@@ -69,10 +69,6 @@ impl FileHandler<Block> for OnActions {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
@@ -161,6 +157,7 @@ fn validate_on_action_internal(
         let mut vd = Validator::new(b, data);
         vd.multi_field_validated_block_sc("delay", sc, validate_duration);
         for token in vd.values() {
+            data.add_event_call(sc, "events", Item::Event, token);
             data.verify_exists(Item::Event, token);
             data.events.check_scope(token, sc);
             if let Some(mut event_sc) = sc.root_for_event(token) {
@@ -187,6 +184,7 @@ fn validate_on_action_internal(
             if token.is("0") {
                 continue;
             }
+            data.add_event_call(sc, "random_events", Item::Event, token);
             data.verify_exists(Item::Event, token);
             data.events.check_scope(token, sc);
             if let Some(mut event_sc) = sc.root_for_event(token) {
@@ -206,6 +204,7 @@ fn validate_on_action_internal(
     vd.multi_field_validated_key_block("first_valid", |key, b, data| {
         let mut vd = Validator::new(b, data);
         for token in vd.values() {
+            data.add_event_call(sc, "first_valid", Item::Event, token);
             data.verify_exists(Item::Event, token);
             data.events.check_scope(token, sc);
             if let Some(mut event_sc) = sc.root_for_event(token) {
@@ -227,6 +226,7 @@ fn validate_on_action_internal(
         let mut vd = Validator::new(b, data);
         vd.multi_field_validated_block_sc("delay", sc, validate_duration);
         for token in vd.values() {
+            data.add_event_call(sc, "on_actions", Item::OnAction, token);
             data.verify_exists(Item::OnAction, token);
             if let Some(mut action_sc) = sc.root_for_action(token) {
                 data.on_actions.validate_call(token, data, &mut action_sc);
@@ -251,6 +251,7 @@ fn validate_on_action_internal(
             if token.is("0") {
                 continue;
             }
+            data.add_event_call(sc, "random_on_action", Item::OnAction, token);
             data.verify_exists(Item::OnAction, token);
             if let Some(mut action_sc) = sc.root_for_action(token) {
                 data.on_actions.validate_call(token, data, &mut action_sc);
@@ -270,6 +271,7 @@ fn validate_on_action_internal(
     vd.multi_field_validated_key_block("first_valid_on_action", |key, b, data| {
         let mut vd = Validator::new(b, data);
         for token in vd.values() {
+            data.add_event_call(sc, "first_valid_on_action", Item::OnAction, token);
             data.verify_exists(Item::OnAction, token);
             if let Some(mut action_sc) = sc.root_for_action(token) {
                 data.on_actions.validate_call(token, data, &mut action_sc);
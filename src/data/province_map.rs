@@ -0,0 +1,179 @@
+//! Geometry checks on `map_data/provinces.png`, where each province is the area of pixels that
+//! have its color.
+
+use bitvec::bitbox;
+use image::{Rgb, RgbImage};
+
+use crate::fileset::FileEntry;
+use crate::helpers::TigerHashMap;
+use crate::report::{warn, ErrorKey};
+
+/// Provinces with fewer pixels than this are reported as too small.
+pub const MIN_PROVINCE_PIXELS: usize = 8;
+
+/// The number of smaller parts of a province to list in a report.
+const MAX_PARTS_LISTED: usize = 5;
+
+/// The area of one color on the province map.
+#[derive(Debug, Default)]
+pub struct ColorArea {
+    /// The number of pixels of this color.
+    pub pixels: usize,
+    /// The separate parts of this area, as their number of pixels and the map coordinates of
+    /// their first pixel. Largest first.
+    pub parts: Vec<(usize, (u32, u32))>,
+}
+
+#[derive(Debug)]
+pub struct ProvinceMap {
+    #[allow(dead_code)] // Not used by all games
+    img: RgbImage,
+    areas: TigerHashMap<Rgb<u8>, ColorArea>,
+}
+
+impl ProvinceMap {
+    /// Find the area of each color on the map. Pixels are connected to the pixels above, below,
+    /// left and right of them, but not to the diagonal ones.
+    ///
+    /// The coordinates of the parts are map coordinates, like the ones [`Self::color_at`] takes.
+    pub fn new(img: RgbImage) -> Self {
+        let (width, height) = img.dimensions();
+        let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+        let mut seen = bitbox![0; width as usize * height as usize];
+        let mut areas: TigerHashMap<Rgb<u8>, ColorArea> = TigerHashMap::default();
+        let mut stack = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if seen[index(x, y)] {
+                    continue;
+                }
+                let color = *img.get_pixel(x, y);
+                seen.set(index(x, y), true);
+                stack.push((x, y));
+                let mut size = 0;
+                while let Some((x, y)) = stack.pop() {
+                    size += 1;
+                    let neighbors = [
+                        (x > 0).then(|| (x - 1, y)),
+                        (x + 1 < width).then_some((x + 1, y)),
+                        (y > 0).then(|| (x, y - 1)),
+                        (y + 1 < height).then_some((x, y + 1)),
+                    ];
+                    for (nx, ny) in neighbors.into_iter().flatten() {
+                        if !seen[index(nx, ny)] && *img.get_pixel(nx, ny) == color {
+                            seen.set(index(nx, ny), true);
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+                let area = areas.entry(color).or_default();
+                area.pixels += size;
+                area.parts.push((size, (x, height - 1 - y)));
+            }
+        }
+        for area in areas.values_mut() {
+            area.parts.sort_by(|(a, _), (b, _)| b.cmp(a));
+        }
+        ProvinceMap { img, areas }
+    }
+
+    #[allow(dead_code)] // Not used by all games
+    pub fn area(&self, color: Rgb<u8>) -> Option<&ColorArea> {
+        self.areas.get(&color)
+    }
+
+    /// Return the color of the pixel at these map coordinates, if they are on the map.
+    /// Map coordinates count y from the bottom of the map, unlike image coordinates.
+    #[allow(dead_code)] // Not used by all games
+    pub fn color_at(&self, x: i64, y: i64) -> Option<Rgb<u8>> {
+        let x = u32::try_from(x).ok()?;
+        let y = u32::try_from(y).ok()?;
+        let y = self.img.height().checked_sub(1)?.checked_sub(y)?;
+        (x < self.img.width()).then(|| *self.img.get_pixel(x, y))
+    }
+
+    /// Report provinces whose area is split into separate parts, and provinces that are very
+    /// small. `name` describes the province that has a color, or returns `None` for colors that
+    /// aren't provinces.
+    pub fn check_areas<F: Fn(Rgb<u8>) -> Option<String>>(&self, entry: &FileEntry, name: F) {
+        let mut areas: Vec<_> = self.areas.iter().collect();
+        areas.sort_unstable_by_key(|(color, _)| color.0);
+        for (&color, area) in areas {
+            let Some(name) = name(color) else {
+                continue;
+            };
+            if area.parts.len() > 1 {
+                let msg = format!("{name} is split into {} separate parts", area.parts.len());
+                let mut coords: Vec<String> = area.parts[1..]
+                    .iter()
+                    .take(MAX_PARTS_LISTED)
+                    .map(|(_, (x, y))| format!("({x}, {y})"))
+                    .collect();
+                if area.parts.len() - 1 > MAX_PARTS_LISTED {
+                    coords.push(format!("and {} more", area.parts.len() - 1 - MAX_PARTS_LISTED));
+                }
+                let info = format!(
+                    "the smaller parts are at {}, counting y from the bottom; stray pixels of a province's color are a common mistake",
+                    coords.join(", ")
+                );
+                warn(ErrorKey::Validation).msg(msg).info(info).loc(entry).push();
+            }
+            if area.pixels < MIN_PROVINCE_PIXELS {
+                let (_, (x, y)) = area.parts[0];
+                let pixels = if area.pixels == 1 { "pixel" } else { "pixels" };
+                let msg = format!("{name} has only {} {pixels}, at ({x}, {y})", area.pixels);
+                let info = format!(
+                    "provinces with fewer than {MIN_PROVINCE_PIXELS} pixels are hard to see and select; the coordinates count y from the bottom"
+                );
+                warn(ErrorKey::Validation).msg(msg).info(info).loc(entry).push();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEA: Rgb<u8> = Rgb([0, 0, 255]);
+    const LAND: Rgb<u8> = Rgb([0, 255, 0]);
+    const ISLE: Rgb<u8> = Rgb([255, 0, 0]);
+
+    #[test]
+    fn test_areas() {
+        // A 6x4 map, drawn as in the image, with the top row first:
+        //   S S S S S S
+        //   L L L S I S
+        //   L L S S S L
+        //   L L S S S S
+        let rows = ["SSSSSS", "LLLSIS", "LLSSSL", "LLSSSS"];
+        let img = RgbImage::from_fn(6, 4, |x, y| match rows[y as usize].as_bytes()[x as usize] {
+            b'L' => LAND,
+            b'I' => ISLE,
+            _ => SEA,
+        });
+        let map = ProvinceMap::new(img);
+
+        // The land is split, and its stray pixel is at the right edge, one row up from the bottom.
+        let land = map.area(LAND).unwrap();
+        assert_eq!(land.pixels, 8);
+        assert_eq!(land.parts.len(), 2);
+        assert_eq!(land.parts[0].0, 7);
+        assert_eq!(land.parts[1], (1, (5, 1)));
+        assert_eq!(map.color_at(5, 1), Some(LAND));
+
+        // The sea goes around the island, so it's one part.
+        let sea = map.area(SEA).unwrap();
+        assert_eq!(sea.pixels, 15);
+        assert_eq!(sea.parts.len(), 1);
+
+        let isle = map.area(ISLE).unwrap();
+        assert_eq!(isle.pixels, 1);
+        assert_eq!(isle.parts, vec![(1, (4, 2))]);
+        assert_eq!(map.color_at(4, 2), Some(ISLE));
+
+        assert_eq!(map.area(Rgb([1, 2, 3])).map(|area| area.pixels), None);
+        assert_eq!(map.color_at(6, 0), None);
+        assert_eq!(map.color_at(0, -1), None);
+    }
+}
@@ -6,6 +6,7 @@ use crate::context::ScopeContext;
 use crate::everything::Everything;
 use crate::fileset::{FileEntry, FileHandler};
 use crate::helpers::{dup_error, exact_dup_error, TigerHashMap, BANNED_NAMES};
+use crate::item::Item;
 use crate::parse::ParserMemory;
 use crate::pdxfile::PdxFile;
 use crate::report::{err, warn, ErrorKey};
@@ -55,12 +56,14 @@ impl ScriptValues {
     }
 
     pub fn validate_call(&self, key: &Token, data: &Everything, sc: &mut ScopeContext) {
+        data.mark_referenced(Item::ScriptValue, key);
         if let Some(item) = self.script_values.get(key.as_str()) {
             item.validate_call(key, data, sc);
         }
     }
 
     pub fn validate_non_dynamic_call(&self, key: &Token, data: &Everything) {
+        data.mark_referenced(Item::ScriptValue, key);
         if let Some(item) = self.script_values.get(key.as_str()) {
             item.validate_non_dynamic_call(data);
         }
@@ -94,10 +97,6 @@ impl FileHandler<Block> for ScriptValues {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
@@ -6,6 +6,7 @@ use crate::context::ScopeContext;
 use crate::everything::Everything;
 use crate::fileset::{FileEntry, FileHandler};
 use crate::helpers::{dup_error, TigerHashMap};
+use crate::item::Item;
 use crate::parse::ParserMemory;
 use crate::pdxfile::PdxFile;
 use crate::report::{err, ErrorKey};
@@ -39,6 +40,7 @@ impl ScriptedLists {
     }
 
     pub fn validate_call(&self, key: &Token, data: &Everything, sc: &mut ScopeContext) {
+        data.mark_referenced(Item::ScriptedList, key);
         if let Some(item) = self.lists.get(key.as_str()) {
             item.validate_call(key, data, sc);
         }
@@ -61,10 +63,6 @@ impl FileHandler<Block> for ScriptedLists {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
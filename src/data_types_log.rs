@@ -0,0 +1,118 @@
+//! Parsing of the `data_types_*.txt` logs that the game writes with the `dump_data_types` console
+//! command.
+//!
+//! This is shared by `--script-docs`, which merges the logs over the built-in datafunction tables,
+//! and by the `munch-data-types` utility, which generates those tables from the logs.
+
+/// The separator between the entries in a `data_types` log.
+const SEPARATOR: &str = "\n-----------------------\n\n";
+
+/// The kinds of definitions in a `data_types` log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinitionType {
+    Type,
+    GlobalPromote,
+    GlobalFunction,
+    Promote,
+    Function,
+}
+
+/// One entry from a `data_types` log, such as:
+/// ```text
+/// Character.GetPrimaryTitle
+/// Definition type: Promote
+/// Return type: Title
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataTypesEntry<'a> {
+    pub definition: DefinitionType,
+    /// The name of the type, promote, or function, without the type it's a member of.
+    pub name: &'a str,
+    /// The type that a promote or function is a member of, or `None` for types and for global
+    /// promotes and functions.
+    pub dtype: Option<&'a str>,
+    /// How many arguments it takes. The logs don't say what types they are.
+    pub nargs: usize,
+    /// The return type. This is `void` if there is none, and `Unknown` if the game doesn't say.
+    pub rtype: &'a str,
+}
+
+/// Split the contents of a `data_types` log into its entries and parse them.
+/// Macros, and entries that can't be parsed, are skipped.
+pub fn data_types_entries(content: &str) -> impl Iterator<Item = DataTypesEntry<'_>> {
+    content.split(SEPARATOR).filter_map(parse_entry)
+}
+
+fn parse_entry(item: &str) -> Option<DataTypesEntry<'_>> {
+    let header = item.lines().next()?.trim();
+    let definition = if item.contains("Definition type: Type") {
+        DefinitionType::Type
+    } else if item.contains("Definition type: Global promote") {
+        DefinitionType::GlobalPromote
+    } else if item.contains("Definition type: Global function") {
+        DefinitionType::GlobalFunction
+    } else if item.contains("Definition type: Promote") {
+        DefinitionType::Promote
+    } else if item.contains("Definition type: Function") {
+        DefinitionType::Function
+    } else {
+        return None;
+    };
+    if definition == DefinitionType::Type {
+        return Some(DataTypesEntry { definition, name: header, dtype: None, nargs: 0, rtype: "" });
+    }
+
+    let (name, nargs) = match header.split_once('(') {
+        Some((name, args)) => (name, args.split(',').count()),
+        None => (header, 0),
+    };
+
+    let mut rtype = match item.split_once("Return type: ").map(|(_, rest)| rest.trim()) {
+        Some("_null_type_") => "void",
+        Some("[unregistered]") | None => "Unknown",
+        // Implement bugfixes to the game's logs
+        Some("float") if name == "IntToFixedPoint" => "CFixedPoint",
+        Some("float") if name == "IntToUnsigned" => "uint32",
+        Some(rtype) => rtype,
+    };
+
+    if matches!(definition, DefinitionType::GlobalPromote | DefinitionType::GlobalFunction) {
+        return Some(DataTypesEntry { definition, name, dtype: None, nargs, rtype });
+    }
+    let (dtype, name) = name.split_once('.')?;
+    if name == "Self" || name == "AccessSelf" {
+        rtype = dtype;
+    }
+    Some(DataTypesEntry { definition, name, dtype: Some(dtype), nargs, rtype })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_types_entries() {
+        let log = "Character\nDefinition type: Type\n-----------------------\n\n\
+            Character.GetPrimaryTitle\nDefinition type: Promote\nReturn type: Title\n-----------------------\n\n\
+            IntToFixedPoint(int32)\nDefinition type: Global function\nReturn type: float\n-----------------------\n\n\
+            Character.Self\nDefinition type: Promote\nReturn type: [unregistered]\n-----------------------\n\n\
+            GetPlayer\nDefinition type: Global macro\n";
+        let entries: Vec<_> = data_types_entries(log).collect();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].definition, DefinitionType::Type);
+        assert_eq!(entries[0].name, "Character");
+        assert_eq!(
+            entries[1],
+            DataTypesEntry {
+                definition: DefinitionType::Promote,
+                name: "GetPrimaryTitle",
+                dtype: Some("Character"),
+                nargs: 0,
+                rtype: "Title",
+            }
+        );
+        assert_eq!(entries[2].nargs, 1);
+        assert_eq!(entries[2].rtype, "CFixedPoint");
+        assert_eq!(entries[3].rtype, "Character");
+    }
+}
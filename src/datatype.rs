@@ -15,12 +15,13 @@ use crate::context::ScopeContext;
 use crate::data::customloca::CustomLocalization;
 use crate::everything::Everything;
 use crate::game::Game;
-use crate::helpers::BiTigerHashMap;
+use crate::helpers::{BiTigerHashMap, TigerHashMap};
 use crate::item::Item;
 #[cfg(any(feature = "ck3", feature = "vic3"))]
 use crate::report::err;
 use crate::report::{warn, ErrorKey};
 use crate::scopes::Scopes;
+use crate::script_docs::ScriptDocs;
 use crate::token::Token;
 
 // Load the game-specific datatype definitions
@@ -678,7 +679,7 @@ pub fn validate_datatypes(
                 // that do a lot of Localize on already localized strings. There's no reason for
                 // it, but I guess it makes them happy.
                 if token.as_str().is_ascii() {
-                    data.localization.verify_exists_lang(token, lang);
+                    data.verify_exists_lang(token, lang);
                 }
             }
         }
@@ -727,14 +728,7 @@ pub fn validate_datatypes(
 }
 
 fn lookup_global_promote(lookup_name: &str) -> Option<(Args, Datatype)> {
-    let global_promotes_map = match Game::game() {
-        #[cfg(feature = "ck3")]
-        Game::Ck3 => &crate::ck3::tables::datafunctions::GLOBAL_PROMOTES_MAP,
-        #[cfg(feature = "vic3")]
-        Game::Vic3 => &crate::vic3::tables::datafunctions::GLOBAL_PROMOTES_MAP,
-        #[cfg(feature = "imperator")]
-        Game::Imperator => &crate::imperator::tables::datafunctions::GLOBAL_PROMOTES_MAP,
-    };
+    let global_promotes_map = global_promotes_map();
 
     if let result @ Some(_) = global_promotes_map.get(lookup_name).copied() {
         return result;
@@ -749,14 +743,7 @@ fn lookup_global_promote(lookup_name: &str) -> Option<(Args, Datatype)> {
 }
 
 fn lookup_global_function(lookup_name: &str) -> Option<(Args, Datatype)> {
-    let global_functions_map = match Game::game() {
-        #[cfg(feature = "ck3")]
-        Game::Ck3 => &crate::ck3::tables::datafunctions::GLOBAL_FUNCTIONS_MAP,
-        #[cfg(feature = "vic3")]
-        Game::Vic3 => &crate::vic3::tables::datafunctions::GLOBAL_FUNCTIONS_MAP,
-        #[cfg(feature = "imperator")]
-        Game::Imperator => &crate::imperator::tables::datafunctions::GLOBAL_FUNCTIONS_MAP,
-    };
+    let global_functions_map = global_functions_map();
     global_functions_map.get(lookup_name).copied()
 }
 
@@ -791,14 +778,7 @@ fn lookup_promote_or_function(ltype: Datatype, vec: &[(Datatype, Args, Datatype)
 }
 
 fn lookup_promote(lookup_name: &str, ltype: Datatype) -> LookupResult {
-    let promotes_map = match Game::game() {
-        #[cfg(feature = "ck3")]
-        Game::Ck3 => &crate::ck3::tables::datafunctions::PROMOTES_MAP,
-        #[cfg(feature = "vic3")]
-        Game::Vic3 => &crate::vic3::tables::datafunctions::PROMOTES_MAP,
-        #[cfg(feature = "imperator")]
-        Game::Imperator => &crate::imperator::tables::datafunctions::PROMOTES_MAP,
-    };
+    let promotes_map = promotes_map();
 
     promotes_map
         .get(lookup_name)
@@ -806,14 +786,7 @@ fn lookup_promote(lookup_name: &str, ltype: Datatype) -> LookupResult {
 }
 
 fn lookup_function(lookup_name: &str, ltype: Datatype) -> LookupResult {
-    let functions_map = match Game::game() {
-        #[cfg(feature = "ck3")]
-        Game::Ck3 => &crate::ck3::tables::datafunctions::FUNCTIONS_MAP,
-        #[cfg(feature = "vic3")]
-        Game::Vic3 => &crate::vic3::tables::datafunctions::FUNCTIONS_MAP,
-        #[cfg(feature = "imperator")]
-        Game::Imperator => &crate::imperator::tables::datafunctions::FUNCTIONS_MAP,
-    };
+    let functions_map = functions_map();
 
     functions_map
         .get(lookup_name)
@@ -853,6 +826,75 @@ fn lookup_alternative(lookup_name: &'static str) -> Option<&'static str> {
     lowercase_datatype_set.get(&CaseInsensitiveStr(lookup_name)).map(|x| x.0)
 }
 
+type GlobalsMap = TigerHashMap<&'static str, (Args, Datatype)>;
+type FunctionsMap = TigerHashMap<&'static str, Vec<(Datatype, Args, Datatype)>>;
+
+pub(crate) fn builtin_global_promotes_map() -> &'static GlobalsMap {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => &crate::ck3::tables::datafunctions::GLOBAL_PROMOTES_MAP,
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => &crate::vic3::tables::datafunctions::GLOBAL_PROMOTES_MAP,
+        #[cfg(feature = "imperator")]
+        Game::Imperator => &crate::imperator::tables::datafunctions::GLOBAL_PROMOTES_MAP,
+    }
+}
+
+pub(crate) fn builtin_global_functions_map() -> &'static GlobalsMap {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => &crate::ck3::tables::datafunctions::GLOBAL_FUNCTIONS_MAP,
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => &crate::vic3::tables::datafunctions::GLOBAL_FUNCTIONS_MAP,
+        #[cfg(feature = "imperator")]
+        Game::Imperator => &crate::imperator::tables::datafunctions::GLOBAL_FUNCTIONS_MAP,
+    }
+}
+
+pub(crate) fn builtin_promotes_map() -> &'static FunctionsMap {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => &crate::ck3::tables::datafunctions::PROMOTES_MAP,
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => &crate::vic3::tables::datafunctions::PROMOTES_MAP,
+        #[cfg(feature = "imperator")]
+        Game::Imperator => &crate::imperator::tables::datafunctions::PROMOTES_MAP,
+    }
+}
+
+pub(crate) fn builtin_functions_map() -> &'static FunctionsMap {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => &crate::ck3::tables::datafunctions::FUNCTIONS_MAP,
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => &crate::vic3::tables::datafunctions::FUNCTIONS_MAP,
+        #[cfg(feature = "imperator")]
+        Game::Imperator => &crate::imperator::tables::datafunctions::FUNCTIONS_MAP,
+    }
+}
+
+// The tables loaded from the game's logs take precedence over the built-in ones.
+
+fn global_promotes_map() -> &'static GlobalsMap {
+    ScriptDocs::get()
+        .and_then(|docs| docs.global_promotes.as_ref())
+        .unwrap_or_else(builtin_global_promotes_map)
+}
+
+fn global_functions_map() -> &'static GlobalsMap {
+    ScriptDocs::get()
+        .and_then(|docs| docs.global_functions.as_ref())
+        .unwrap_or_else(builtin_global_functions_map)
+}
+
+fn promotes_map() -> &'static FunctionsMap {
+    ScriptDocs::get().and_then(|docs| docs.promotes.as_ref()).unwrap_or_else(builtin_promotes_map)
+}
+
+fn functions_map() -> &'static FunctionsMap {
+    ScriptDocs::get().and_then(|docs| docs.functions.as_ref()).unwrap_or_else(builtin_functions_map)
+}
+
 fn datatype_and_scope_map() -> &'static Lazy<BiTigerHashMap<Datatype, Scopes>> {
     match Game::game() {
         #[cfg(feature = "ck3")]
@@ -13,10 +13,13 @@ use strum::IntoEnumIterator;
 use crate::block::Block;
 use crate::context::ScopeContext;
 use crate::everything::Everything;
-use crate::helpers::{dup_error, exact_dup_advice, exact_dup_error, TigerHashMap, TigerHashSet};
+use crate::helpers::{
+    dup_error, exact_dup_advice, exact_dup_error, similar_names, TigerHashMap, TigerHashSet,
+};
 use crate::item::Item;
 use crate::lowercase::Lowercase;
-use crate::token::Token;
+use crate::pathtable::PathTableIndex;
+use crate::token::{Loc, Token};
 
 /// The main database of game items.
 #[derive(Debug)]
@@ -59,6 +62,93 @@ impl Db {
         self.database[item as usize].insert(key.as_str(), DbEntry { key, block, kind });
     }
 
+    /// Move the items and flags from `other` into this database, where `other` holds the items
+    /// reloaded from a single file. Items defined by files later in the load order keep
+    /// overriding the reloaded ones.
+    ///
+    /// Returns the keys of the items that were added.
+    pub fn merge_reloaded(&mut self, mut other: Db) -> Vec<(Item, &'static str)> {
+        let mut added = Vec::new();
+        for itype in Item::iter() {
+            for (key, entry) in other.database[itype as usize].drain() {
+                if let Some(later) = self.database[itype as usize].get(key) {
+                    if later.key.loc.idx > entry.key.loc.idx {
+                        if later.key.loc.kind <= entry.key.loc.kind {
+                            if later.block.equivalent(&entry.block) {
+                                exact_dup_error(&later.key, &entry.key, &itype.to_string());
+                            } else {
+                                dup_error(&later.key, &entry.key, &itype.to_string());
+                            }
+                        }
+                        continue;
+                    }
+                }
+                added.push((itype, key));
+                self.add(itype, entry.key, entry.block, entry.kind);
+            }
+            for token in other.flags[itype as usize].drain() {
+                self.add_flag(itype, token);
+            }
+        }
+        added
+    }
+
+    /// Remove all items and flags that were defined in the file that `file` points into.
+    ///
+    /// Returns the keys of the removed items, so that the caller can look for other definitions
+    /// that they were overriding.
+    pub fn remove_file(&mut self, file: Loc) -> Vec<(Item, &'static str)> {
+        let mut removed = Vec::new();
+        for itype in Item::iter() {
+            self.database[itype as usize].retain(|key, entry| {
+                let keep = !entry.key.loc.same_file(file);
+                if !keep {
+                    removed.push((itype, *key));
+                }
+                keep
+            });
+            // Flags are deduplicated by name, so a flag that is also set in another file will
+            // disappear here if this file happened to be the first to set it.
+            self.flags[itype as usize].retain(|token| !token.loc.same_file(file));
+        }
+        self.rebuild_lowercase();
+        removed
+    }
+
+    /// Take the item `key` out of the database, if it's there.
+    pub fn take(&mut self, item: Item, key: &str) -> Option<DbEntry> {
+        self.database[item as usize].remove(key)
+    }
+
+    /// Put an item back that was taken out with [`Db::take`].
+    pub fn restore(&mut self, item: Item, entry: DbEntry) {
+        self.items_lc[item as usize].insert(Lowercase::new(entry.key.as_str()), entry.key.as_str());
+        self.database[item as usize].insert(entry.key.as_str(), entry);
+    }
+
+    /// Like [`Db::add_subitems`], but only for the listed items.
+    pub fn add_subitems_for(&mut self, items: &[(Item, &'static str)]) {
+        for &(itype, key) in items {
+            if let Some(entry) = self.database[itype as usize].remove(key) {
+                entry.kind.add_subitems(&entry.key, &entry.block, self);
+                self.database[itype as usize].insert(key, entry);
+            }
+        }
+    }
+
+    fn rebuild_lowercase(&mut self) {
+        for itype in Item::iter() {
+            let lc = &mut self.items_lc[itype as usize];
+            lc.clear();
+            for key in self.database[itype as usize].keys() {
+                lc.insert(Lowercase::new(key), key);
+            }
+            for token in &self.flags[itype as usize] {
+                lc.insert(Lowercase::new(token.as_str()), token.as_str());
+            }
+        }
+    }
+
     pub fn add_exact_dup_ok(
         &mut self,
         item: Item,
@@ -108,6 +198,36 @@ impl Db {
         });
     }
 
+    /// Like [`Db::validate`], but only for the items defined in the given files.
+    pub fn validate_files(&self, files: &TigerHashSet<PathTableIndex>, data: &Everything) {
+        self.database.par_iter().for_each(|hash| {
+            hash.par_iter().filter(|(_, entry)| files.contains(&entry.key.loc.idx)).for_each(
+                |(_, entry)| {
+                    entry.kind.validate(&entry.key, &entry.block, data);
+                },
+            );
+        });
+    }
+
+    /// Return the files that define items whose key or contents mention any of the `keys`.
+    ///
+    /// This is a textual check, so it will find some false positives. A token counts as a mention
+    /// if it matches a key as a whole or in one of its `:` or `.` separated parts.
+    pub fn files_mentioning(
+        &self,
+        keys: &TigerHashSet<&'static str>,
+    ) -> TigerHashSet<PathTableIndex> {
+        let mentions = |token: &Token| mentions_key(token.as_str(), keys);
+        self.database
+            .par_iter()
+            .flat_map_iter(|hash| {
+                hash.values()
+                    .filter(|entry| mentions(&entry.key) || entry.block.any_token(&mentions))
+                    .map(|entry| entry.key.loc.idx)
+            })
+            .collect()
+    }
+
     pub fn exists(&self, item: Item, key: &str) -> bool {
         self.database[item as usize].contains_key(key) || self.flags[item as usize].contains(key)
     }
@@ -116,6 +236,11 @@ impl Db {
         self.items_lc[item as usize].contains_key(key)
     }
 
+    /// Return the keys of the items of this type that are spelled similarly to `key`.
+    pub fn similar_items(&self, item: Item, key: &str) -> Vec<&'static str> {
+        similar_names(key, self.items_lc[item as usize].values().copied())
+    }
+
     #[allow(dead_code)]
     pub fn get_item<T: DbKind + Any>(&self, item: Item, key: &str) -> Option<(&Token, &Block, &T)> {
         if let Some(entry) = self.database[item as usize].get(key) {
@@ -207,6 +332,12 @@ impl Db {
     }
 }
 
+/// Return true iff `word` is one of the `keys`, either as a whole or in one of its `:` or `.`
+/// separated parts.
+pub(crate) fn mentions_key(word: &str, keys: &TigerHashSet<&'static str>) -> bool {
+    keys.contains(word) || word.split([':', '.']).any(|part| keys.contains(part))
+}
+
 #[derive(Debug)]
 pub struct DbEntry {
     key: Token,
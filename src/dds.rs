@@ -2,8 +2,9 @@
 
 use std::fs::{metadata, File};
 use std::io::{Read, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::block::Block;
 use crate::fileset::{FileEntry, FileHandler};
 use crate::helpers::TigerHashMap;
 use crate::parse::ParserMemory;
@@ -11,9 +12,61 @@ use crate::report::{err, tips, warn, ErrorKey};
 #[cfg(feature = "ck3")]
 use crate::token::Token;
 
-const DDS_HEADER_SIZE: usize = 124;
+/// The size of the magic number plus the header.
+const DDS_HEADER_SIZE: usize = 128;
 const DDS_HEIGHT_OFFSET: usize = 12;
 const DDS_WIDTH_OFFSET: usize = 16;
+const DDS_MIPMAP_COUNT_OFFSET: usize = 28;
+const DDS_PIXELFORMAT_FLAGS_OFFSET: usize = 80;
+const DDS_FOURCC_OFFSET: usize = 84;
+const DDS_RGB_BIT_COUNT_OFFSET: usize = 88;
+const DDS_CAPS2_OFFSET: usize = 112;
+
+/// The size of the extra header that follows when the fourCC is `DX10`.
+const DX10_HEADER_SIZE: usize = 20;
+const DX10_FORMAT_OFFSET: usize = 0;
+const DX10_MISC_FLAG_OFFSET: usize = 8;
+
+const DDPF_FOURCC: u32 = 0x4;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xfc00;
+const DX10_MISC_TEXTURECUBE: u32 = 0x4;
+
+/// The DXGI formats that have both an sRGB and a linear variant, as (linear, sRGB).
+const DXGI_SRGB_PAIRS: &[(u32, u32)] =
+    &[(28, 29), (71, 72), (74, 75), (77, 78), (87, 91), (88, 93), (98, 99)];
+
+/// What the game expects of the textures in a directory. Unset fields aren't checked.
+#[derive(Clone, Copy, Debug, Default)]
+struct TextureRules {
+    /// Whether the texture should be block compressed (BC1 to BC7).
+    compressed: Option<bool>,
+    /// Whether the texture should have a full mip chain.
+    mipmaps: Option<bool>,
+    /// Whether the texture should be a cube map.
+    cube: Option<bool>,
+    /// Whether the texture should be in an sRGB format, as opposed to a linear one.
+    srgb: Option<bool>,
+}
+
+impl TextureRules {
+    fn merge(&mut self, other: TextureRules) {
+        self.compressed = other.compressed.or(self.compressed);
+        self.mipmaps = other.mipmaps.or(self.mipmaps);
+        self.cube = other.cube.or(self.cube);
+        self.srgb = other.srgb.or(self.srgb);
+    }
+}
+
+/// The built-in expectations per directory. They can be added to and overridden from the
+/// `textures` block in the config file.
+const DEFAULT_RULES: &[(&str, TextureRules)] = &[
+    (
+        "gfx/models",
+        TextureRules { compressed: Some(true), mipmaps: Some(true), cube: None, srgb: None },
+    ),
+    ("gfx/map", TextureRules { compressed: None, mipmaps: Some(true), cube: None, srgb: None }),
+];
 
 fn from_le32(buffer: &[u8], offset: usize) -> u32 {
     u32::from(buffer[offset])
@@ -25,9 +78,32 @@ fn from_le32(buffer: &[u8], offset: usize) -> u32 {
 #[derive(Clone, Debug, Default)]
 pub struct DdsFiles {
     dds_files: TigerHashMap<String, DdsInfo>,
+    /// Texture expectations from the config file, per directory.
+    rules: Vec<(PathBuf, TextureRules)>,
 }
 
 impl DdsFiles {
+    /// Combine the expectations for the directories that contain this file, with the more
+    /// specific directories taking precedence, and the config file over the built-in ones.
+    fn rules_for(&self, path: &Path) -> TextureRules {
+        let mut matching: Vec<(&Path, &TextureRules)> = DEFAULT_RULES
+            .iter()
+            .map(|(dir, rules)| (Path::new(dir), rules))
+            .chain(self.rules.iter().map(|(dir, rules)| (dir.as_path(), rules)))
+            .filter(|(dir, _)| path.starts_with(dir))
+            .collect();
+        matching.sort_by_key(|(dir, _)| dir.components().count());
+
+        let mut result = TextureRules::default();
+        if path.file_name().is_some_and(|name| name.to_string_lossy().contains("cubemap")) {
+            result.cube = Some(true);
+        }
+        for (_, rules) in matching {
+            result.merge(*rules);
+        }
+        result
+    }
+
     fn load_dds(entry: &FileEntry) -> Result<Option<DdsInfo>> {
         if metadata(entry.fullpath())?.len() == 0 {
             warn(ErrorKey::ImageFormat).msg("empty file").loc(entry).push();
@@ -47,13 +123,23 @@ impl DdsFiles {
             err(ErrorKey::ImageFormat).msg("not a DDS file").loc(entry).push();
             return Ok(None);
         }
-        Ok(Some(DdsInfo::new(&buffer)))
+        let mut dx10 = [0; DX10_HEADER_SIZE];
+        let has_dx10 = &buffer[DDS_FOURCC_OFFSET..DDS_FOURCC_OFFSET + 4] == b"DX10";
+        if has_dx10 {
+            f.read_exact(&mut dx10)?;
+        }
+        Ok(Some(DdsInfo::new(&buffer, has_dx10.then_some(&dx10[..]))))
     }
 
     fn handle_dds(&mut self, entry: &FileEntry, info: DdsInfo) {
         self.dds_files.insert(entry.path().to_string_lossy().to_string(), info);
     }
 
+    /// Return the width and height of the texture at this path, if it was loaded.
+    pub fn get_size(&self, path: &str) -> Option<(u32, u32)> {
+        self.dds_files.get(path).map(|info| (info.width, info.height))
+    }
+
     #[cfg(feature = "ck3")]
     pub fn validate_frame(&self, key: &Token, width: u32, height: u32, frame: u32) {
         // Note: `frame` is 1-based
@@ -73,17 +159,36 @@ impl DdsFiles {
 }
 
 impl FileHandler<DdsInfo> for DdsFiles {
+    fn config(&mut self, config: &Block) {
+        if let Some(block) = config.get_field_block("textures") {
+            for (key, block) in block.iter_definitions_warn() {
+                let rules = TextureRules {
+                    compressed: block.get_field_bool("compressed"),
+                    mipmaps: block.get_field_bool("mipmaps"),
+                    cube: block.get_field_bool("cube"),
+                    srgb: block.get_field_bool("srgb"),
+                };
+                self.rules.push((PathBuf::from(key.as_str()), rules));
+            }
+        }
+    }
+
     fn subpath(&self) -> PathBuf {
         PathBuf::from("gfx")
     }
 
-    fn load_file(&self, entry: &FileEntry, _parser: &ParserMemory) -> Option<DdsInfo> {
-        if !entry.filename().to_string_lossy().ends_with(".dds") {
-            return None;
-        }
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.filename().to_string_lossy().ends_with(".dds")
+    }
 
+    fn load_file(&self, entry: &FileEntry, _parser: &ParserMemory) -> Option<DdsInfo> {
         match Self::load_dds(entry) {
-            Ok(info) => info,
+            Ok(info) => {
+                if let Some(info) = &info {
+                    info.validate(entry, self.rules_for(entry.path()));
+                }
+                info
+            }
             Err(e) => {
                 err(ErrorKey::ReadError)
                     .msg("could not read dds header")
@@ -102,16 +207,308 @@ impl FileHandler<DdsInfo> for DdsFiles {
 
 #[derive(Copy, Clone, Debug)]
 pub struct DdsInfo {
-    #[allow(dead_code)] // vic3 doesn't use
     width: u32,
-    #[allow(dead_code)] // vic3 doesn't use
     height: u32,
+    /// The number of mip levels, including the full-size image.
+    mipmaps: u32,
+    /// The BC compression number, or `None` for uncompressed textures.
+    compression: Option<u8>,
+    /// The bits per pixel of uncompressed textures, or 0 if the header doesn't say.
+    bits: u32,
+    /// Whether the format is sRGB, for formats that come in both an sRGB and a linear variant.
+    /// DDS files without a DX10 header don't say.
+    srgb: Option<bool>,
+    /// The number of cube map faces, or `None` if it's not a cube map.
+    cube_faces: Option<u32>,
 }
 
 impl DdsInfo {
-    pub fn new(header: &[u8]) -> Self {
+    /// Parse the header, which includes the magic number at the start, and the DX10 header if
+    /// there is one.
+    pub fn new(header: &[u8], dx10: Option<&[u8]>) -> Self {
         let height = from_le32(header, DDS_HEIGHT_OFFSET);
         let width = from_le32(header, DDS_WIDTH_OFFSET);
-        Self { width, height }
+        let mipmaps = from_le32(header, DDS_MIPMAP_COUNT_OFFSET).max(1);
+        let caps2 = from_le32(header, DDS_CAPS2_OFFSET);
+
+        let compression;
+        let mut bits = 0;
+        let mut srgb = None;
+        let mut cube_faces = None;
+        if let Some(dx10) = dx10 {
+            let format = from_le32(dx10, DX10_FORMAT_OFFSET);
+            compression = match format {
+                70..=72 => Some(1),
+                73..=75 => Some(2),
+                76..=78 => Some(3),
+                79..=81 => Some(4),
+                82..=84 => Some(5),
+                94..=96 => Some(6),
+                97..=99 => Some(7),
+                _ => None,
+            };
+            for &(linear, srgb_format) in DXGI_SRGB_PAIRS {
+                if format == linear {
+                    srgb = Some(false);
+                } else if format == srgb_format {
+                    srgb = Some(true);
+                }
+            }
+            if from_le32(dx10, DX10_MISC_FLAG_OFFSET) & DX10_MISC_TEXTURECUBE != 0 {
+                cube_faces = Some(6);
+            }
+        } else {
+            let fourcc = &header[DDS_FOURCC_OFFSET..DDS_FOURCC_OFFSET + 4];
+            compression = if from_le32(header, DDS_PIXELFORMAT_FLAGS_OFFSET) & DDPF_FOURCC == 0 {
+                None
+            } else {
+                match fourcc {
+                    b"DXT1" => Some(1),
+                    b"DXT2" | b"DXT3" => Some(2),
+                    b"DXT4" | b"DXT5" => Some(3),
+                    b"ATI1" | b"BC4U" | b"BC4S" => Some(4),
+                    b"ATI2" | b"BC5U" | b"BC5S" => Some(5),
+                    _ => None,
+                }
+            };
+            if compression.is_none() {
+                bits = from_le32(header, DDS_RGB_BIT_COUNT_OFFSET);
+            }
+            if caps2 & DDSCAPS2_CUBEMAP != 0 {
+                cube_faces = Some((caps2 & DDSCAPS2_CUBEMAP_ALLFACES).count_ones());
+            }
+        }
+        Self { width, height, mipmaps, compression, bits, srgb, cube_faces }
+    }
+
+    /// Describe the pixel format for use in reports.
+    fn format_name(&self) -> String {
+        match self.compression {
+            Some(n) => format!("BC{n}"),
+            None if self.bits > 0 => format!("uncompressed {}-bit", self.bits),
+            None => "uncompressed".to_owned(),
+        }
+    }
+
+    /// The number of mip levels in a full mip chain.
+    fn full_mipmaps(&self) -> u32 {
+        u32::BITS - self.width.max(self.height).max(1).leading_zeros()
+    }
+
+    /// The number of mip levels that count as a complete chain. Block compressed textures are
+    /// stored in blocks of 4x4 pixels, so their chains often stop at 4x4.
+    fn enough_mipmaps(&self) -> u32 {
+        let full = self.full_mipmaps();
+        if self.compression.is_some() {
+            full.saturating_sub(2).max(1)
+        } else {
+            full
+        }
+    }
+
+    /// Report what the game is known to reject or to render badly, taking into account what it
+    /// expects of textures in this file's directory.
+    fn validate(&self, entry: &FileEntry, rules: TextureRules) {
+        let (width, height) = (self.width, self.height);
+        if self.compression.is_some() && (width % 4 != 0 || height % 4 != 0) {
+            let msg =
+                format!("compressed texture of {width}x{height} is not a multiple of 4 in size");
+            let info = "the game can't load block compressed textures of that size";
+            err(ErrorKey::ImageFormat).msg(msg).info(info).loc(entry).push();
+        }
+
+        if let Some(faces) = self.cube_faces {
+            if faces < 6 {
+                let msg = format!("cube map has only {faces} of its 6 faces");
+                err(ErrorKey::ImageFormat).msg(msg).loc(entry).push();
+            }
+            if width != height {
+                let msg = format!("cube map faces of {width}x{height} are not square");
+                err(ErrorKey::ImageFormat).msg(msg).loc(entry).push();
+            }
+        }
+        match (rules.cube, self.cube_faces.is_some()) {
+            (Some(true), false) => {
+                let msg = "texture is not a cube map";
+                let info = "environment textures are expected to be cube maps with 6 faces";
+                err(ErrorKey::ImageFormat).msg(msg).info(info).loc(entry).push();
+            }
+            (Some(false), true) => {
+                let msg = "texture is a cube map where a flat texture is expected";
+                warn(ErrorKey::ImageFormat).msg(msg).loc(entry).push();
+            }
+            _ => (),
+        }
+
+        if rules.compressed == Some(true) && self.compression.is_none() {
+            let msg = format!("texture is {}", self.format_name());
+            let info = "textures here are expected to be BC compressed; uncompressed textures take several times as much video memory";
+            warn(ErrorKey::ImageFormat).msg(msg).info(info).loc(entry).push();
+        }
+
+        if rules.mipmaps == Some(true) {
+            let full = self.full_mipmaps();
+            let enough = self.enough_mipmaps();
+            if self.mipmaps == 1 && enough > 1 {
+                if width.is_power_of_two() && height.is_power_of_two() {
+                    let msg = "texture has no mipmaps";
+                    let info = "without mipmaps it will shimmer and look grainy from a distance";
+                    warn(ErrorKey::ImageFormat).msg(msg).info(info).loc(entry).push();
+                } else {
+                    let msg = format!(
+                        "texture of {width}x{height} has no mipmaps and is not a power of two"
+                    );
+                    let info = "resize it to a power of two and save it with mipmaps, or it will shimmer and look grainy from a distance";
+                    warn(ErrorKey::ImageFormat).msg(msg).info(info).loc(entry).push();
+                }
+            } else if self.mipmaps < enough {
+                let msg = format!("texture has only {} of {full} mipmap levels", self.mipmaps);
+                tips(ErrorKey::ImageFormat).msg(msg).loc(entry).push();
+            }
+        }
+
+        if let (Some(expected), Some(srgb)) = (rules.srgb, self.srgb) {
+            if expected != srgb {
+                let (is, should) = if srgb { ("sRGB", "linear") } else { ("linear", "sRGB") };
+                let msg = format!("texture is in {is} format but should be {should}");
+                let info = "the wrong color space makes a texture look washed out or too dark";
+                warn(ErrorKey::ImageFormat).msg(msg).info(info).loc(entry).push();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put_le32(buffer: &mut [u8], offset: usize, value: u32) {
+        buffer[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Build a DDS header. A `fourcc` of `None` makes an uncompressed texture of `bits` per pixel.
+    fn header(
+        width: u32,
+        height: u32,
+        mipmaps: u32,
+        fourcc: Option<&[u8; 4]>,
+        bits: u32,
+        caps2: u32,
+    ) -> [u8; DDS_HEADER_SIZE] {
+        let mut header = [0; DDS_HEADER_SIZE];
+        header[..4].copy_from_slice(b"DDS ");
+        put_le32(&mut header, DDS_HEIGHT_OFFSET, height);
+        put_le32(&mut header, DDS_WIDTH_OFFSET, width);
+        put_le32(&mut header, DDS_MIPMAP_COUNT_OFFSET, mipmaps);
+        if let Some(fourcc) = fourcc {
+            put_le32(&mut header, DDS_PIXELFORMAT_FLAGS_OFFSET, DDPF_FOURCC);
+            header[DDS_FOURCC_OFFSET..DDS_FOURCC_OFFSET + 4].copy_from_slice(fourcc);
+        } else {
+            put_le32(&mut header, DDS_RGB_BIT_COUNT_OFFSET, bits);
+        }
+        put_le32(&mut header, DDS_CAPS2_OFFSET, caps2);
+        header
+    }
+
+    fn dx10(format: u32, misc_flag: u32) -> [u8; DX10_HEADER_SIZE] {
+        let mut dx10 = [0; DX10_HEADER_SIZE];
+        put_le32(&mut dx10, DX10_FORMAT_OFFSET, format);
+        put_le32(&mut dx10, DX10_MISC_FLAG_OFFSET, misc_flag);
+        dx10
+    }
+
+    /// Parse a header with a DX10 extension, as 148 bytes in a row like in the file.
+    fn parse_dx10(width: u32, height: u32, mipmaps: u32, format: u32, misc_flag: u32) -> DdsInfo {
+        let mut file = header(width, height, mipmaps, Some(b"DX10"), 0, 0).to_vec();
+        file.extend_from_slice(&dx10(format, misc_flag));
+        assert_eq!(file.len(), 148);
+        let (header, dx10) = file.split_at(DDS_HEADER_SIZE);
+        DdsInfo::new(header, Some(dx10))
+    }
+
+    #[test]
+    fn test_legacy_header() {
+        let info = DdsInfo::new(&header(256, 128, 9, Some(b"DXT5"), 0, 0), None);
+        assert_eq!((info.width, info.height, info.mipmaps), (256, 128, 9));
+        assert_eq!(info.compression, Some(3));
+        assert_eq!((info.bits, info.srgb, info.cube_faces), (0, None, None));
+
+        for (fourcc, compression) in
+            [(b"DXT1", 1), (b"DXT3", 2), (b"ATI1", 4), (b"BC4U", 4), (b"ATI2", 5), (b"BC5S", 5)]
+        {
+            let info = DdsInfo::new(&header(4, 4, 1, Some(fourcc), 0, 0), None);
+            assert_eq!(info.compression, Some(compression));
+        }
+
+        // Uncompressed, and a mipmap count of 0 means there's only the full image.
+        let info = DdsInfo::new(&header(64, 64, 0, None, 32, 0), None);
+        assert_eq!((info.compression, info.bits, info.mipmaps), (None, 32, 1));
+        assert_eq!(info.format_name(), "uncompressed 32-bit");
+
+        // An unknown fourCC isn't a compression.
+        let info = DdsInfo::new(&header(64, 64, 1, Some(b"ABCD"), 0, 0), None);
+        assert_eq!(info.compression, None);
+
+        let all_faces = DDSCAPS2_CUBEMAP | DDSCAPS2_CUBEMAP_ALLFACES;
+        let info = DdsInfo::new(&header(64, 64, 1, Some(b"DXT1"), 0, all_faces), None);
+        assert_eq!(info.cube_faces, Some(6));
+        let two_faces = DDSCAPS2_CUBEMAP | 0x400 | 0x800;
+        let info = DdsInfo::new(&header(64, 64, 1, Some(b"DXT1"), 0, two_faces), None);
+        assert_eq!(info.cube_faces, Some(2));
+        // The face flags don't count without the cube map flag.
+        let info = DdsInfo::new(&header(64, 64, 1, Some(b"DXT1"), 0, 0x400), None);
+        assert_eq!(info.cube_faces, None);
+    }
+
+    #[test]
+    fn test_dx10_header() {
+        let info = parse_dx10(512, 256, 10, 98, 0);
+        assert_eq!((info.width, info.height, info.mipmaps), (512, 256, 10));
+        assert_eq!(info.compression, Some(7));
+        assert_eq!((info.srgb, info.cube_faces), (Some(false), None));
+
+        for (format, compression) in [(71, 1), (74, 2), (77, 3), (80, 4), (83, 5), (95, 6)] {
+            assert_eq!(parse_dx10(4, 4, 1, format, 0).compression, Some(compression));
+        }
+
+        for &(linear, srgb) in DXGI_SRGB_PAIRS {
+            assert_eq!(parse_dx10(4, 4, 1, linear, 0).srgb, Some(false));
+            assert_eq!(parse_dx10(4, 4, 1, srgb, 0).srgb, Some(true));
+        }
+
+        // R8G8B8A8_UNORM_SRGB is uncompressed, and R32G32B32A32_FLOAT has no sRGB variant.
+        let info = parse_dx10(4, 4, 1, 29, 0);
+        assert_eq!((info.compression, info.srgb), (None, Some(true)));
+        assert_eq!(parse_dx10(4, 4, 1, 2, 0).srgb, None);
+
+        let info = parse_dx10(64, 64, 7, 71, DX10_MISC_TEXTURECUBE);
+        assert_eq!(info.cube_faces, Some(6));
+    }
+
+    #[test]
+    fn test_enough_mipmaps() {
+        let uncompressed =
+            |width, height| DdsInfo::new(&header(width, height, 1, None, 32, 0), None);
+        let compressed =
+            |width, height| DdsInfo::new(&header(width, height, 1, Some(b"DXT1"), 0, 0), None);
+
+        // 256 down to 1 is 9 levels, and the compressed chain may stop at 4x4.
+        assert_eq!(uncompressed(256, 256).full_mipmaps(), 9);
+        assert_eq!(uncompressed(256, 256).enough_mipmaps(), 9);
+        assert_eq!(compressed(256, 256).full_mipmaps(), 9);
+        assert_eq!(compressed(256, 256).enough_mipmaps(), 7);
+
+        // The larger side decides.
+        assert_eq!(uncompressed(512, 8).enough_mipmaps(), 10);
+        assert_eq!(compressed(8, 512).enough_mipmaps(), 8);
+
+        // Non-power-of-two sizes round down.
+        assert_eq!(uncompressed(300, 200).enough_mipmaps(), 9);
+
+        // Small compressed textures need at least the full-size image.
+        assert_eq!(compressed(4, 4).enough_mipmaps(), 1);
+        assert_eq!(compressed(8, 8).enough_mipmaps(), 2);
+        assert_eq!(uncompressed(1, 1).enough_mipmaps(), 1);
+        assert_eq!(uncompressed(0, 0).enough_mipmaps(), 1);
     }
 }
@@ -6,10 +6,12 @@ use crate::data::effect_localization::EffectLocalization;
 use crate::desc::validate_desc;
 use crate::everything::Everything;
 use crate::game::Game;
+use crate::game_version::VersionRange;
 use crate::item::Item;
 use crate::lowercase::Lowercase;
 use crate::report::{err, fatal, tips, warn, ErrorKey};
 use crate::scopes::{scope_iterator, Scopes};
+use crate::script_docs;
 use crate::script_value::validate_script_value;
 use crate::token::Token;
 use crate::tooltipped::Tooltipped;
@@ -111,6 +113,18 @@ pub fn validate_effect_internal(
     });
 }
 
+/// Return the names of the built-in effects that are spelled similarly to `name`.
+pub fn similar_effects(name: &str) -> Vec<&'static str> {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => crate::ck3::tables::effects::similar_effects(name),
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => crate::vic3::tables::effects::similar_effects(name),
+        #[cfg(feature = "imperator")]
+        Game::Imperator => crate::imperator::tables::effects::similar_effects(name),
+    }
+}
+
 /// Validate a single effect field
 pub fn validate_effect_field(
     caller: &Lowercase,
@@ -122,6 +136,7 @@ pub fn validate_effect_field(
     tooltipped: Tooltipped,
 ) {
     if let Some(effect) = data.get_effect(key) {
+        data.add_event_call(sc, "scripted_effect", Item::ScriptedEffect, key);
         match bv {
             BV::Value(token) => {
                 if !effect.macro_parms().is_empty() {
@@ -183,7 +198,7 @@ pub fn validate_effect_field(
         Game::Imperator => crate::imperator::tables::effects::scope_effect,
     };
 
-    if let Some((inscopes, effect)) = scope_effect(key, data) {
+    if let Some((inscopes, effect)) = script_docs::effect(key, scope_effect(key, data)) {
         sc.expect(inscopes, &Reason::Token(key.clone()));
         match effect {
             Effect::Yes => {
@@ -343,8 +358,11 @@ pub fn validate_effect_field(
                 }
             }
             Effect::Removed(version, explanation) => {
-                let msg = format!("`{key}` was removed in {version}");
-                warn(ErrorKey::Removed).msg(msg).info(explanation).loc(key).push();
+                // Still valid if the installed game is older than the version it was removed in.
+                if !VersionRange::until(version).includes_installed() {
+                    let msg = format!("`{key}` was removed in {version}");
+                    warn(ErrorKey::Removed).msg(msg).info(explanation).loc(key).push();
+                }
             }
             Effect::Unchecked => (),
             #[cfg(any(feature = "ck3", feature = "vic3"))]
@@ -388,7 +406,7 @@ pub fn validate_effect_field(
 
     // Check if it's a target = { target_scope } block.
     sc.open_builder();
-    if validate_scope_chain(key, data, sc, matches!(cmp, Comparator::Equals(Question))) {
+    if validate_scope_chain(key, data, sc, matches!(cmp, Comparator::Equals(Question)), true) {
         sc.finalize_builder();
         if key.starts_with("flag:") {
             let msg = "as of 1.9, flag literals cannot be used on the left-hand side";
@@ -234,23 +234,18 @@ pub fn validate_switch(
 }
 
 pub fn validate_trigger_event(
-    _key: &Token,
+    key: &Token,
     bv: &BV,
     data: &Everything,
     sc: &mut ScopeContext,
     _tooltipped: Tooltipped,
 ) {
     match bv {
-        BV::Value(token) => {
-            data.verify_exists(Item::Event, token);
-            data.events.check_scope(token, sc);
-            if let Some(mut event_sc) = sc.root_for_event(token) {
-                data.events.validate_call(token, data, &mut event_sc);
-            }
-        }
+        BV::Value(token) => validate_triggered_event(key, token, data, sc),
         BV::Block(block) => {
             let mut vd = Validator::new(block, data);
             vd.set_case_sensitive(false);
+            vd.set_event_call_label(key.as_str());
             vd.field_event("id", sc);
             vd.field_action("on_action", sc);
             #[cfg(feature = "ck3")]
@@ -267,3 +262,12 @@ pub fn validate_trigger_event(
         }
     }
 }
+
+fn validate_triggered_event(key: &Token, token: &Token, data: &Everything, sc: &mut ScopeContext) {
+    data.add_event_call(sc, key.as_str(), Item::Event, token);
+    data.verify_exists(Item::Event, token);
+    data.events.check_scope(token, sc);
+    if let Some(mut event_sc) = sc.root_for_event(token) {
+        data.events.validate_call(token, data, &mut event_sc);
+    }
+}
@@ -0,0 +1,248 @@
+//! A graph of which events and `on_action`s trigger which other events and `on_action`s,
+//! for exporting to Graphviz or to other tools.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::everything::Everything;
+use crate::helpers::{TigerHashMap, TigerHashSet};
+use crate::item::Item;
+use crate::references::{is_mod, loc_json};
+use crate::token::Loc;
+
+/// One call from an event, `on_action`, or other script item to an event or `on_action`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Edge {
+    from: String,
+    to: (Item, String),
+    /// The effect or field that does the triggering, such as `trigger_event` or `random_events`.
+    label: String,
+    loc: Loc,
+}
+
+/// The calls seen during validation.
+#[derive(Debug, Default)]
+pub(crate) struct EventGraph {
+    edges: Mutex<Vec<Edge>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum NodeKind {
+    Event,
+    OnAction,
+    ScriptedEffect,
+    /// Decisions, interactions, and the other items that can start a chain.
+    Other,
+}
+
+impl NodeKind {
+    fn name(self) -> &'static str {
+        match self {
+            NodeKind::Event => "event",
+            NodeKind::OnAction => "on_action",
+            NodeKind::ScriptedEffect => "scripted_effect",
+            NodeKind::Other => "other",
+        }
+    }
+}
+
+struct Node {
+    kind: NodeKind,
+    key: String,
+    /// Where it's defined, if it's an item with a definition.
+    loc: Option<Loc>,
+    /// True for events in the mod that nothing triggers.
+    orphan: bool,
+}
+
+impl Node {
+    fn id(&self) -> String {
+        format!("{}:{}", self.kind.name(), self.key)
+    }
+}
+
+impl EventGraph {
+    pub(crate) fn add(&self, from: &str, itype: Item, to: &str, label: &str, loc: Loc) {
+        let edge = Edge {
+            from: from.to_owned(),
+            to: (itype, to.to_owned()),
+            label: label.to_owned(),
+            loc,
+        };
+        self.edges.lock().unwrap().push(edge);
+    }
+
+    /// Collect the nodes and edges that involve the mods. Vanilla's own event chains are left out,
+    /// to keep the graph to a useful size.
+    fn build(&self, data: &Everything) -> (Vec<Node>, Vec<(String, String, Edge)>) {
+        let mut defined: TigerHashMap<(NodeKind, &str), Loc> = TigerHashMap::default();
+        for (itype, kind) in [
+            (Item::Event, NodeKind::Event),
+            (Item::OnAction, NodeKind::OnAction),
+            (Item::ScriptedEffect, NodeKind::ScriptedEffect),
+        ] {
+            for token in data.iter_keys(itype) {
+                // Prefer the mod's definition when it overrides vanilla's.
+                let loc = defined.entry((kind, token.as_str())).or_insert(token.loc);
+                if is_mod(token.loc.kind) {
+                    *loc = token.loc;
+                }
+            }
+        }
+        // The callers are only known by their key. Guess what they are.
+        let caller_kind = |key: &str| {
+            [NodeKind::Event, NodeKind::OnAction, NodeKind::ScriptedEffect]
+                .into_iter()
+                .find(|kind| defined.contains_key(&(*kind, key)))
+                .unwrap_or(NodeKind::Other)
+        };
+        let target_kind = |itype: Item| match itype {
+            Item::Event => NodeKind::Event,
+            Item::OnAction => NodeKind::OnAction,
+            _ => NodeKind::ScriptedEffect,
+        };
+
+        let mut edges = self.edges.lock().unwrap().clone();
+        edges.sort_unstable();
+        // Events are validated again for each chain they are part of, so the same call is seen
+        // many times. Calls in macro expansions differ only in their link to the macro call.
+        edges.dedup_by(|a, b| {
+            a.from == b.from
+                && a.to == b.to
+                && a.label == b.label
+                && (a.loc.idx, a.loc.line, a.loc.column) == (b.loc.idx, b.loc.line, b.loc.column)
+        });
+
+        let mut called = TigerHashSet::default();
+        let mut kept = Vec::new();
+        for edge in edges {
+            let from = (caller_kind(&edge.from), edge.from.clone());
+            let to = (target_kind(edge.to.0), edge.to.1.clone());
+            called.insert(to.clone());
+            let to_loc = defined.get(&(to.0, to.1.as_str()));
+            if is_mod(edge.loc.kind) || to_loc.is_some_and(|loc| is_mod(loc.kind)) {
+                kept.push((from, to, edge));
+            }
+        }
+        // Scripted effects are only interesting if they lead to events or on_actions.
+        loop {
+            let callers: TigerHashSet<_> = kept.iter().map(|(from, _, _)| from.clone()).collect();
+            let before = kept.len();
+            kept.retain(|(_, to, _)| to.0 != NodeKind::ScriptedEffect || callers.contains(to));
+            if kept.len() == before {
+                break;
+            }
+        }
+
+        let mut nodes: TigerHashMap<(NodeKind, String), Option<Loc>> = TigerHashMap::default();
+        for (from, to, _) in &kept {
+            for (kind, key) in [from, to] {
+                let loc = defined.get(&(*kind, key.as_str())).copied();
+                nodes.insert((*kind, key.clone()), loc);
+            }
+        }
+        for ((kind, key), loc) in &defined {
+            if matches!(kind, NodeKind::Event | NodeKind::OnAction) && is_mod(loc.kind) {
+                nodes.insert((*kind, (*key).to_owned()), Some(*loc));
+            }
+        }
+
+        let mut nodes: Vec<Node> = nodes
+            .into_iter()
+            .map(|((kind, key), loc)| {
+                let orphan = kind == NodeKind::Event
+                    && loc.is_some_and(|loc| is_mod(loc.kind))
+                    && !called.contains(&(kind, key.clone()));
+                Node { kind, key, loc, orphan }
+            })
+            .collect();
+        nodes.sort_unstable_by(|a, b| (a.kind, &a.key).cmp(&(b.kind, &b.key)));
+        let mut edges: Vec<_> = kept
+            .into_iter()
+            .map(|((from_kind, from), (to_kind, to), edge)| {
+                (format!("{}:{from}", from_kind.name()), format!("{}:{to}", to_kind.name()), edge)
+            })
+            .collect();
+        edges.sort_by(|(from_a, to_a, _), (from_b, to_b, _)| (from_a, to_a).cmp(&(from_b, to_b)));
+        (nodes, edges)
+    }
+
+    /// Write the graph in Graphviz DOT format. Events that nothing triggers are highlighted.
+    pub(crate) fn write_dot(&self, data: &Everything, fullpath: &Path) -> Result<()> {
+        let (nodes, edges) = self.build(data);
+        let mut writer = BufWriter::new(File::create(fullpath)?);
+        writeln!(writer, "digraph events {{")?;
+        writeln!(writer, "    rankdir=LR;")?;
+        writeln!(writer, "    node [shape=box];")?;
+        for node in &nodes {
+            let mut attrs = format!("label={}", quote(&node.key));
+            match node.kind {
+                NodeKind::Event => (),
+                NodeKind::OnAction => attrs.push_str(", shape=ellipse"),
+                NodeKind::ScriptedEffect => attrs.push_str(", shape=component"),
+                NodeKind::Other => attrs.push_str(", shape=house"),
+            }
+            if node.orphan {
+                attrs.push_str(", style=filled, fillcolor=orange, tooltip=\"not triggered\"");
+            }
+            writeln!(writer, "    {} [{attrs}];", quote(&node.id()))?;
+        }
+        // The same call can be made from several places. Show it only once.
+        let mut seen = TigerHashSet::default();
+        for (from, to, edge) in &edges {
+            if seen.insert((from, to, &edge.label)) {
+                writeln!(
+                    writer,
+                    "    {} -> {} [label={}];",
+                    quote(from),
+                    quote(to),
+                    quote(&edge.label)
+                )?;
+            }
+        }
+        writeln!(writer, "}}")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the graph as a JSON object with a list of nodes and a list of edges.
+    pub(crate) fn write_json(&self, data: &Everything, fullpath: &Path) -> Result<()> {
+        let (nodes, edges) = self.build(data);
+        let nodes: Vec<Value> = nodes
+            .iter()
+            .map(|node| {
+                json!({
+                    "id": node.id(),
+                    "kind": node.kind.name(),
+                    "key": node.key,
+                    "location": node.loc.map(loc_json),
+                    "orphan": node.orphan,
+                })
+            })
+            .collect();
+        let edges: Vec<Value> = edges
+            .into_iter()
+            .map(|(from, to, edge)| {
+                json!({
+                    "from": from,
+                    "to": to,
+                    "label": edge.label,
+                    "location": loc_json(edge.loc),
+                })
+            })
+            .collect();
+        let writer = BufWriter::new(File::create(fullpath)?);
+        serde_json::to_writer_pretty(writer, &json!({ "nodes": nodes, "edges": edges }))?;
+        Ok(())
+    }
+}
+
+/// Quote a string as a DOT identifier.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
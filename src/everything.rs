@@ -4,12 +4,12 @@
 //! that individual functions can access all the defined game items.
 
 use std::borrow::Cow;
+use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
-#[cfg(feature = "ck3")]
 use std::sync::RwLock;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use rayon::{scope, Scope};
 use strum::IntoEnumIterator;
 use thiserror::Error;
@@ -28,6 +28,7 @@ use crate::ck3::data::{
     prov_history::ProvinceHistories,
     prov_terrain::{ProvinceProperties, ProvinceTerrains},
     provinces::Ck3Provinces,
+    start_dates::validate_start_dates,
     title_history::TitleHistories,
     titles::Titles,
     traits::Traits,
@@ -35,7 +36,7 @@ use crate::ck3::data::{
 };
 #[cfg(feature = "ck3")]
 use crate::ck3::tables::misc::*;
-use crate::config_load::{check_for_legacy_ignore, load_filter};
+use crate::config_load::{check_for_legacy_ignore, load_filter, load_severity_overrides};
 use crate::context::ScopeContext;
 #[cfg(any(feature = "ck3", feature = "vic3"))]
 use crate::data::data_binding::DataBindings;
@@ -54,27 +55,38 @@ use crate::data::{
     scripted_modifiers::ScriptedModifiers,
     scripted_triggers::{Trigger, Triggers},
 };
-use crate::db::{Db, DbKind};
+use crate::db::{mentions_key, Db, DbKind};
 use crate::dds::DdsFiles;
-use crate::fileset::{FileEntry, FileKind, Fileset};
+use crate::event_graph::EventGraph;
+use crate::fileset::{FileEntry, FileHandler, FileKind, Fileset};
 use crate::game::Game;
-#[cfg(feature = "ck3")]
-use crate::helpers::TigerHashSet;
+use crate::game_version::detect_game_version;
+use crate::helpers::{did_you_mean, TigerHashMap, TigerHashSet};
 #[cfg(feature = "imperator")]
 use crate::imperator::data::{decisions::Decisions, provinces::ImperatorProvinces};
 #[cfg(feature = "imperator")]
 use crate::imperator::tables::misc::*;
 use crate::item::{Item, ItemLoader};
+use crate::loca_report;
 use crate::lowercase::Lowercase;
 use crate::macros::MACRO_MAP;
+use crate::map_images::validate_map_images;
+use crate::on_action::on_action_scopecontext;
 #[cfg(feature = "vic3")]
 use crate::parse::json::parse_json_file;
 use crate::parse::ParserMemory;
+use crate::pathtable::PathTable;
 use crate::pdxfile::PdxFile;
+use crate::references::References;
 #[cfg(feature = "ck3")]
 use crate::report::err;
-use crate::report::{report, set_output_style, ErrorKey, OutputStyle, Severity};
+use crate::report::{
+    forget_ignore_comments, keep_ignore_uses_outside, report, set_output_style,
+    start_ignore_loading, start_ignore_validating, stored_source_file, warn, will_maybe_log,
+    ErrorKey, OutputStyle, Severity,
+};
 use crate::rivers::Rivers;
+use crate::sound_banks::SoundBanks;
 use crate::token::{Loc, Token};
 #[cfg(feature = "vic3")]
 use crate::vic3::data::{
@@ -95,6 +107,28 @@ pub enum FilesError {
     ConfigUnreadable { path: PathBuf },
 }
 
+/// A record of what was reloaded by calls to [`Everything::reload_file`], so that
+/// [`Everything::validate_changes`] can limit validation to what may be affected.
+#[derive(Debug, Default)]
+pub struct Changes {
+    /// The files that were reloaded.
+    files: Vec<Loc>,
+    /// The keys of the items defined in the reloaded files, both before and after reloading.
+    keys: TigerHashSet<&'static str>,
+    /// The names of the special-purpose databases that were reloaded.
+    handlers: TigerHashSet<&'static str>,
+    /// Set if a reloaded file can affect the validation of other items in ways that can't be
+    /// traced by item keys. Then everything has to be validated again.
+    validate_all: bool,
+}
+
+impl Changes {
+    /// Return true iff nothing has been reloaded.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
 /// A record of everything known about the game and mod being validated.
 ///
 /// References to [`Everything`] are passed down through nearly all of the validation logic, so
@@ -119,12 +153,26 @@ pub struct Everything {
     #[cfg(feature = "ck3")] // happens not to be used by vic3
     warned_defines: RwLock<TigerHashSet<String>>,
 
+    /// The keys of the items that were referred to, for the item types that `--unused` checks,
+    /// if they are being tracked.
+    used_items: Option<TigerHashMap<Item, RwLock<TigerHashSet<String>>>>,
+
+    /// The references to items seen during validation, if they are being recorded.
+    references: Option<References>,
+
+    /// The calls between events and `on_action`s seen during validation, if they are being
+    /// recorded.
+    event_graph: Option<EventGraph>,
+
     /// Tracks all the files (vanilla and mods) that are relevant to the current validation.
     pub(crate) fileset: Fileset,
 
     /// Tracks specifically the .dds files, and their formats and sizes.
     pub(crate) dds: DdsFiles,
 
+    /// The sound event names listed in the FMOD strings banks.
+    pub(crate) sound_banks: SoundBanks,
+
     /// A general database of item types. Most items go here. The ones that need special handling
     /// go in the separate databases listed below.
     pub(crate) database: Db,
@@ -245,13 +293,21 @@ impl Everything {
         fileset.scan_all()?;
         fileset.finalize();
 
+        if let Some(vanilla_dir) = vanilla_dir {
+            detect_game_version(vanilla_dir);
+        }
+
         Ok(Everything {
             parser: ParserMemory::default(),
             fileset,
             dds: DdsFiles::default(),
+            sound_banks: SoundBanks::default(),
             config,
             #[cfg(feature = "ck3")]
             warned_defines: RwLock::new(TigerHashSet::default()),
+            used_items: None,
+            references: None,
+            event_graph: None,
             database: Db::default(),
             localization: Localization::default(),
             scripted_lists: ScriptedLists::default(),
@@ -313,6 +369,7 @@ impl Everything {
     pub fn load_config_filtering_rules(&self) {
         check_for_legacy_ignore(&self.config);
         load_filter(&self.config);
+        load_severity_overrides(&self.config);
     }
 
     /// Load the `OutputStyle` settings from the config.
@@ -372,25 +429,226 @@ impl Everything {
 
     fn load_pdx_files(&mut self, loader: &ItemLoader) {
         let path = PathBuf::from(loader.itype().path());
-        for mut block in self.fileset.filter_map_under(&path, |entry| {
+        for block in self.fileset.filter_map_under(&path, |entry| {
             if entry.filename().to_string_lossy().ends_with(loader.extension()) {
                 PdxFile::read_encoded(entry, loader.encoding(), &self.parser)
             } else {
                 None
             }
         }) {
-            if loader.whole_file() {
-                let fname = block.loc.filename();
-                // unwrap is safe here because of the ends_with check above.
-                let key = fname.strip_suffix(loader.extension()).unwrap();
-                let key = Token::new(key, block.loc);
-                (loader.adder())(&mut self.database, key, block);
-            } else {
-                for (key, block) in block.drain_definitions_warn() {
-                    (loader.adder())(&mut self.database, key, block);
+            Self::add_pdx_block(loader, &mut self.database, block);
+        }
+    }
+
+    /// Add the items from one file's `block` to the database, as directed by the `loader`.
+    /// The file must have passed the loader's extension check.
+    fn add_pdx_block(loader: &ItemLoader, db: &mut Db, mut block: Block) {
+        if loader.whole_file() {
+            let fname = block.loc.filename();
+            // unwrap is safe here because of the caller's extension check.
+            let key = fname.strip_suffix(loader.extension()).unwrap();
+            let key = Token::new(key, block.loc);
+            (loader.adder())(db, key, block);
+        } else {
+            for (key, block) in block.drain_definitions_warn() {
+                (loader.adder())(db, key, block);
+            }
+        }
+    }
+
+    /// Return the [`ItemLoader`]s that would load this file.
+    fn loaders_for(entry: &FileEntry) -> Vec<&'static ItemLoader> {
+        inventory::iter::<ItemLoader>
+            .into_iter()
+            .filter(|loader| {
+                loader.for_game(Game::game())
+                    && entry.path().starts_with(loader.itype().path())
+                    && entry.filename().to_string_lossy().ends_with(loader.extension())
+            })
+            .collect()
+    }
+
+    /// Return true iff the file is one that other files depend on while they are being loaded,
+    /// so that changing it means everything has to be loaded again.
+    #[allow(unused_variables)] // imperator has no such files
+    fn is_load_dependency(path: &Path) -> bool {
+        #[cfg(feature = "ck3")]
+        if path.starts_with("reader_export") {
+            return true;
+        }
+        #[cfg(feature = "vic3")]
+        if path.starts_with(Item::TerrainMask.path()) {
+            return true;
+        }
+        false
+    }
+
+    /// Return the keys of all items that are defined in the file that `file` points into.
+    fn keys_in_file(&self, file: Loc) -> Vec<&'static str> {
+        Item::iter()
+            .filter(|itype| !matches!(itype, Item::File | Item::Localization))
+            .flat_map(|itype| self.iter_keys(itype))
+            .filter(|token| token.loc.same_file(file))
+            .map(Token::as_str)
+            .collect()
+    }
+
+    /// Reload a single file after it changed on disk (or its override changed, see
+    /// [`set_file_override`](crate::fileset::set_file_override)), and swap its items in the
+    /// databases. What was reloaded is recorded in `changes`, for use by
+    /// [`Everything::validate_changes`].
+    ///
+    /// Returns false if the file can't be reloaded on its own. That is the case for files that
+    /// are new since this `Everything` was created, and for files that affect how other files are
+    /// loaded. The caller should create a new `Everything` instead.
+    pub fn reload_file(&mut self, fullpath: &Path, changes: &mut Changes) -> bool {
+        let Some(entry) = self.fileset.get_entry_by_fullpath(fullpath).cloned() else {
+            return false;
+        };
+        if Self::is_load_dependency(entry.path()) {
+            return false;
+        }
+        let file = Loc::from(&entry);
+        start_ignore_loading();
+        forget_ignore_comments(file);
+        changes.files.push(file);
+        // Both the keys defined before and after the reload count as changed.
+        changes.keys.extend(self.keys_in_file(file));
+
+        let loaders = Self::loaders_for(&entry);
+        if !loaders.is_empty() {
+            self.reload_pdx_file(&entry, &loaders);
+            #[cfg(feature = "ck3")]
+            crate::ck3::data::buildings::Building::finalize(&mut self.database);
+        }
+        self.reload_handlers(&entry, changes);
+
+        changes.keys.extend(self.keys_in_file(file));
+        true
+    }
+
+    /// Swap the items loaded from `entry` by the `loaders` in the database.
+    fn reload_pdx_file(&mut self, entry: &FileEntry, loaders: &[&ItemLoader]) {
+        let removed = self.database.remove_file(Loc::from(entry));
+        let mut reloaded = Db::default();
+        for loader in loaders {
+            if let Some(block) = PdxFile::read_encoded(entry, loader.encoding(), &self.parser) {
+                Self::add_pdx_block(loader, &mut reloaded, block);
+            }
+        }
+        let mut added = self.database.merge_reloaded(reloaded);
+
+        // Items that are no longer defined by this file may have been overriding definitions in
+        // other files. Those have to be loaded again.
+        let lost: Vec<_> =
+            removed.into_iter().filter(|&(itype, key)| !self.database.exists(itype, key)).collect();
+        if !lost.is_empty() {
+            for loader in loaders {
+                let path = PathBuf::from(loader.itype().path());
+                for other in self.fileset.get_files_under(&path) {
+                    if other == entry
+                        || !other.filename().to_string_lossy().ends_with(loader.extension())
+                    {
+                        continue;
+                    }
+                    let Some(block) = PdxFile::read_encoded(other, loader.encoding(), &self.parser)
+                    else {
+                        continue;
+                    };
+                    let mut db = Db::default();
+                    Self::add_pdx_block(loader, &mut db, block);
+                    for &(itype, key) in &lost {
+                        if let Some(dbentry) = db.take(itype, key) {
+                            self.database.restore(itype, dbentry);
+                            added.push((itype, key));
+                        }
+                    }
                 }
             }
         }
+        added.sort_unstable();
+        added.dedup();
+        self.database.add_subitems_for(&added);
+    }
+
+    /// Reload every special-purpose database that loads `entry`. They are reloaded as a whole,
+    /// because they don't support swapping out a single file's items.
+    fn reload_handlers(&mut self, entry: &FileEntry, changes: &mut Changes) {
+        macro_rules! reload {
+            ($field:ident) => {
+                if Self::reload_handler(&self.fileset, &self.parser, &mut self.$field, entry) {
+                    changes.handlers.insert(stringify!($field));
+                }
+            };
+            // These databases are used in ways that can't be traced by item keys, for example
+            // through implied localization keys or icon paths.
+            ($field:ident, untraceable) => {
+                if Self::reload_handler(&self.fileset, &self.parser, &mut self.$field, entry) {
+                    changes.validate_all = true;
+                }
+            };
+        }
+
+        reload!(dds, untraceable);
+        reload!(sound_banks, untraceable);
+        reload!(events);
+        reload!(localization, untraceable);
+        reload!(scripted_lists);
+        reload!(defines, untraceable);
+        reload!(scripted_modifiers);
+        reload!(script_values);
+        reload!(triggers);
+        reload!(effects);
+        reload!(assets);
+        reload!(gui);
+        reload!(on_actions);
+        reload!(coas);
+        reload!(music);
+        #[cfg(feature = "ck3")]
+        {
+            reload!(interaction_cats);
+            reload!(province_histories);
+            reload!(province_properties);
+            reload!(province_terrains);
+            reload!(gameconcepts, untraceable);
+            reload!(titles);
+            reload!(characters);
+            reload!(traits);
+            reload!(title_history);
+            reload!(doctrines);
+            reload!(menatarmstypes);
+            reload!(data_bindings, untraceable);
+            reload!(provinces_ck3, untraceable);
+            reload!(wars);
+        }
+        #[cfg(feature = "vic3")]
+        {
+            reload!(history);
+            reload!(provinces_vic3, untraceable);
+            reload!(data_bindings, untraceable);
+        }
+        #[cfg(feature = "imperator")]
+        {
+            reload!(decisions_imperator);
+            reload!(provinces_imperator, untraceable);
+        }
+    }
+
+    /// Reset `handler` and load all its files again, if it is one that loads `entry`.
+    /// This is done even if `entry` can no longer be parsed, so that its old contents are dropped.
+    /// Returns true iff it was reloaded.
+    fn reload_handler<T: Send, H: FileHandler<T> + Default>(
+        fileset: &Fileset,
+        parser: &ParserMemory,
+        handler: &mut H,
+        entry: &FileEntry,
+    ) -> bool {
+        if !entry.path().starts_with(handler.subpath()) || !handler.handles(entry) {
+            return false;
+        }
+        *handler = H::default();
+        fileset.handle(handler, parser);
+        true
     }
 
     fn load_all_normal_pdx_files(&mut self) {
@@ -404,6 +662,7 @@ impl Everything {
     fn load_all_generic(&mut self) {
         scope(|s| {
             s.spawn(|_| self.fileset.handle(&mut self.dds, &self.parser));
+            s.spawn(|_| self.fileset.handle(&mut self.sound_banks, &self.parser));
             s.spawn(|_| self.fileset.handle(&mut self.events, &self.parser));
             s.spawn(|_| self.fileset.handle(&mut self.localization, &self.parser));
             s.spawn(|_| self.fileset.handle(&mut self.scripted_lists, &self.parser));
@@ -458,6 +717,7 @@ impl Everything {
     }
 
     pub fn load_all(&mut self) {
+        start_ignore_loading();
         #[cfg(feature = "ck3")]
         self.load_reader_export();
         self.load_all_generic();
@@ -485,6 +745,7 @@ impl Everything {
         s.spawn(|_| self.gui.validate(self));
         s.spawn(|_| self.on_actions.validate(self));
         s.spawn(|_| self.coas.validate(self));
+        s.spawn(|_| validate_map_images(self));
         s.spawn(|_| self.music.validate(self));
     }
 
@@ -505,6 +766,7 @@ impl Everything {
         s.spawn(|_| self.provinces_ck3.validate(self));
         s.spawn(|_| self.wars.validate(self));
         s.spawn(|_| Climate::validate_all(&self.database, self));
+        s.spawn(|_| validate_start_dates(self));
     }
 
     #[cfg(feature = "vic3")]
@@ -522,7 +784,12 @@ impl Everything {
         s.spawn(|_| self.provinces_imperator.validate(self));
     }
 
+    #[allow(clippy::missing_panics_doc)] // only panics on poisoned lock
     pub fn validate_all(&self) {
+        start_ignore_validating();
+        // Validation may be repeated after a reload, so warn about missing defines again.
+        #[cfg(feature = "ck3")]
+        self.warned_defines.write().unwrap().clear();
         scope(|s| {
             self.validate_all_generic(s);
             match Game::game() {
@@ -537,6 +804,123 @@ impl Everything {
         self.database.validate(self);
 
         self.localization.validate_pass2(self);
+        self.localization.check_translations();
+    }
+
+    /// Validate again only what may be affected by the reloads recorded in `changes`: the items
+    /// defined in the reloaded files, and the items whose definitions mention any of the changed
+    /// item keys. The special-purpose databases are validated as a whole if they were reloaded
+    /// or if any of their mod files mention a changed key. Vanilla files are not searched for
+    /// such mentions by those databases, since their reports are usually not shown.
+    ///
+    /// Some changes can't be traced this way, for example changes to localization. Then this
+    /// falls back to [`Everything::validate_all`].
+    ///
+    /// Returns the full paths of the files that were validated completely, meaning that their
+    /// earlier validation reports can be discarded in favor of the new ones.
+    /// Returns `None` if everything was validated.
+    #[allow(clippy::missing_panics_doc)] // only panics on poisoned lock
+    pub fn validate_changes(&self, changes: &Changes) -> Option<Vec<PathBuf>> {
+        if changes.validate_all {
+            self.validate_all();
+            return None;
+        }
+        start_ignore_validating();
+        #[cfg(feature = "ck3")]
+        self.warned_defines.write().unwrap().clear();
+
+        let mut files = self.database.files_mentioning(&changes.keys);
+        files.extend(changes.files.iter().map(|loc| loc.idx));
+        #[allow(unused_variables)] // imperator has no crosschecks
+        let touches = |itype: Item| {
+            files.iter().any(|&idx| PathTable::lookup_path(idx).starts_with(itype.path()))
+        };
+        let mut subpaths = Vec::new();
+        scope(|s| {
+            macro_rules! validate {
+                ($field:ident) => {
+                    let subpath = self.$field.subpath();
+                    if changes.handlers.contains(stringify!($field))
+                        || self.mod_files_mention(&subpath, &changes.keys)
+                    {
+                        s.spawn(|_| self.$field.validate(self));
+                        subpaths.push(subpath);
+                    }
+                };
+            }
+
+            validate!(scripted_lists);
+            validate!(scripted_modifiers);
+            validate!(script_values);
+            validate!(triggers);
+            validate!(effects);
+            validate!(events);
+            validate!(assets);
+            validate!(gui);
+            validate!(on_actions);
+            validate!(coas);
+            validate!(music);
+            #[cfg(feature = "ck3")]
+            {
+                validate!(interaction_cats);
+                validate!(province_histories);
+                validate!(province_properties);
+                validate!(province_terrains);
+                validate!(titles);
+                validate!(characters);
+                validate!(traits);
+                validate!(title_history);
+                validate!(doctrines);
+                validate!(menatarmstypes);
+                validate!(wars);
+                if touches(Item::Climate) {
+                    s.spawn(|_| Climate::validate_all(&self.database, self));
+                }
+            }
+            #[cfg(feature = "vic3")]
+            {
+                validate!(history);
+                if touches(Item::StrategicRegion) {
+                    s.spawn(|_| StrategicRegion::crosscheck(self));
+                }
+                if touches(Item::BuyPackage) {
+                    s.spawn(|_| BuyPackage::crosscheck(self));
+                }
+            }
+            #[cfg(feature = "imperator")]
+            validate!(decisions_imperator);
+        });
+        self.database.validate_files(&files, self);
+
+        let mut validated: Vec<PathBuf> =
+            files.into_iter().map(|idx| PathTable::lookup_fullpath(idx).to_path_buf()).collect();
+        for subpath in subpaths {
+            validated
+                .extend(self.fileset.get_files_under(&subpath).iter().map(|e| e.fullpath().into()));
+        }
+        validated.sort_unstable();
+        validated.dedup();
+        keep_ignore_uses_outside(&validated);
+        Some(validated)
+    }
+
+    /// Return true iff any of the mod's files under `subpath` contain a word that mentions any of
+    /// the `keys`, in the same way as [`Db::files_mentioning`].
+    ///
+    /// This searches the file contents as they were last parsed, so files that were never parsed
+    /// as script don't count.
+    fn mod_files_mention(&self, subpath: &Path, keys: &TigerHashSet<&'static str>) -> bool {
+        !keys.is_empty()
+            && self
+                .fileset
+                .get_files_under(subpath)
+                .iter()
+                .filter(|entry| !entry.kind().counts_as_vanilla())
+                .filter_map(|entry| stored_source_file(entry.fullpath()))
+                .any(|text| {
+                    text.split(|c: char| c.is_whitespace() || "=<>!?{}[]()\"#|,;$@".contains(c))
+                        .any(|word| mentions_key(word, keys))
+                })
     }
 
     pub fn check_rivers(&mut self) {
@@ -552,9 +936,167 @@ impl Everything {
         self.localization.check_pod_loca(self);
     }
 
+    /// Return the root directories of the mod being validated and of the other loaded mods.
+    /// These are the directories in which files can be expected to change while working on the mod.
+    pub fn mod_roots(&self) -> Vec<&Path> {
+        self.fileset.mod_roots()
+    }
+
+    /// Start remembering which items are referred to during validation, so that
+    /// [`Everything::check_unused`] can report the ones that never are.
+    pub fn track_unused(&mut self) {
+        self.used_items = Some(
+            Item::iter()
+                .filter(|itype| itype.needs_reference())
+                .map(|itype| (itype, RwLock::default()))
+                .collect(),
+        );
+    }
+
+    /// Warn about unused localization, files, and items.
+    /// [`Everything::track_unused`] must have been called before validation for the items to be
+    /// checked.
     pub fn check_unused(&mut self) {
         self.localization.check_unused(self);
         self.fileset.check_unused_dds(self);
+        self.check_unused_items();
+    }
+
+    /// Warn about the items that are defined in the mod but never referred to, for the item types
+    /// where that means they do nothing.
+    fn check_unused_items(&self) {
+        if self.used_items.is_none() {
+            return;
+        }
+        for itype in Item::iter().filter(|itype| itype.needs_reference()) {
+            let mut vec: Vec<&Token> = self
+                .iter_keys(itype)
+                .filter(|key| key.loc.kind == FileKind::Mod && !self.item_used(itype, key))
+                .collect();
+            if itype == Item::OnAction {
+                // The builtin on_actions are called by the game engine.
+                vec.retain(|key| on_action_scopecontext(key, self).is_none());
+            }
+            for key in vec {
+                let msg = format!("{itype} {key} is never used");
+                warn(ErrorKey::UnusedItem).msg(msg).loc(key).push();
+            }
+        }
+    }
+
+    /// Return true iff the item was referred to during validation.
+    /// Only meaningful for the item types where [`Item::needs_reference`] is true.
+    fn item_used(&self, itype: Item, key: &Token) -> bool {
+        let used = match itype {
+            Item::GuiTemplate => self.gui.template_used(key.as_str()),
+            Item::GuiType => self.gui.type_used(key.as_str()),
+            _ => false,
+        };
+        used || self
+            .used_items
+            .as_ref()
+            .and_then(|used_items| used_items.get(&itype))
+            .is_some_and(|used| used.read().unwrap().contains(key.as_str()))
+    }
+
+    /// Start recording where items are referred to during validation, so that they can be
+    /// written out with [`Everything::write_references_json`].
+    pub fn record_references(&mut self) {
+        self.references = Some(References::default());
+    }
+
+    /// Write a JSON index of where the items are defined and where they were referred to.
+    /// [`Everything::record_references`] must have been called before validation.
+    pub fn write_references_json(&self, fullpath: &Path) -> Result<()> {
+        let Some(references) = &self.references else {
+            bail!("references were not recorded");
+        };
+        references.write_json(self, fullpath)
+    }
+
+    /// Start recording which events and `on_action`s trigger which others, so that the graph can
+    /// be written out with [`Everything::write_event_graph`].
+    pub fn record_event_graph(&mut self) {
+        self.event_graph = Some(EventGraph::default());
+    }
+
+    /// Write the graph of events and `on_action`s. It is written in Graphviz DOT format if the
+    /// file name ends in `.dot` or `.gv`, and as JSON otherwise.
+    /// [`Everything::record_event_graph`] must have been called before validation.
+    pub fn write_event_graph(&self, fullpath: &Path) -> Result<()> {
+        let Some(event_graph) = &self.event_graph else {
+            bail!("the event graph was not recorded");
+        };
+        let ext = fullpath.extension().and_then(OsStr::to_str).unwrap_or_default();
+        if ext.eq_ignore_ascii_case("dot") || ext.eq_ignore_ascii_case("gv") {
+            event_graph.write_dot(self, fullpath)
+        } else {
+            event_graph.write_json(self, fullpath)
+        }
+    }
+
+    /// Write a report of which of the mod's localization keys are missing, stale, or untranslated
+    /// in each language. It is written as CSV if the file name ends in `.csv`, and as JSON
+    /// otherwise.
+    pub fn write_loca_report(&self, fullpath: &Path) -> Result<()> {
+        let ext = fullpath.extension().and_then(OsStr::to_str).unwrap_or_default();
+        if ext.eq_ignore_ascii_case("csv") {
+            loca_report::write_csv(&self.localization, fullpath)
+        } else {
+            loca_report::write_json(&self.localization, fullpath)
+        }
+    }
+
+    /// Write the texts of the mod's localization in all languages to a snapshot file, so that
+    /// later runs can find the translations that were not updated when the English text changed.
+    /// Returns the number of keys written.
+    pub fn write_loca_snapshot(&self, fullpath: &Path) -> Result<usize> {
+        loca_report::write_snapshot(&self.localization, fullpath)
+    }
+
+    /// Report the translations that are the same as in the snapshot file, while the English text
+    /// has changed since. Returns the number of keys whose English text changed.
+    pub fn check_loca_snapshot(&self, fullpath: &Path) -> Result<usize> {
+        loca_report::check_snapshot(&self.localization, fullpath)
+    }
+
+    /// Note that the event or `on_action` that `sc` is validating triggers `token`, by way of the
+    /// effect or field `label`. `itype` is the type of `token`, which is an event, an `on_action`,
+    /// or a scripted effect.
+    pub(crate) fn add_event_call(
+        &self,
+        sc: &ScopeContext,
+        label: &str,
+        itype: Item,
+        token: &Token,
+    ) {
+        if let Some(event_graph) = &self.event_graph {
+            event_graph.add(sc.caller().as_str(), itype, token.as_str(), label, token.loc);
+        }
+    }
+
+    fn add_reference(&self, itype: Item, key: &str, loc: Loc) {
+        if let Some(references) = &self.references {
+            references.add(itype, key, loc);
+        }
+    }
+
+    /// Note that `token` refers to an item, for lookups that don't go through
+    /// [`Everything::verify_exists`].
+    pub(crate) fn mark_referenced(&self, itype: Item, token: &Token) {
+        self.mark_item_used(itype, token.as_str());
+        self.add_reference(itype, token.as_str(), token.loc);
+    }
+
+    /// Remember that the item was referred to, if it's of a type that `--unused` checks and
+    /// that is being tracked.
+    fn mark_item_used(&self, itype: Item, key: &str) {
+        if let Some(used) = self.used_items.as_ref().and_then(|used_items| used_items.get(&itype)) {
+            // Most items are referred to many times, so check before taking the write lock.
+            if !used.read().unwrap().contains(key) {
+                used.write().unwrap().insert(key.to_owned());
+            }
+        }
     }
 
     pub(crate) fn item_has_property(&self, itype: Item, key: &str, property: &str) -> bool {
@@ -737,7 +1279,7 @@ impl Everything {
         match itype {
             Item::File => self.fileset.mark_used(key),
             Item::Localization => self.localization.mark_used(key),
-            _ => (),
+            _ => self.mark_item_used(itype, key),
         }
     }
 
@@ -756,6 +1298,7 @@ impl Everything {
         token: &Token,
         max_sev: Severity,
     ) {
+        self.add_reference(itype, key, token.loc);
         match itype {
             Item::Entry => self.fileset.verify_entry_exists(key, token, max_sev),
             Item::File => self.fileset.verify_exists_implied(key, token, max_sev),
@@ -785,6 +1328,7 @@ impl Everything {
                 }
             }
             _ => {
+                self.mark_item_used(itype, key);
                 if !self.item_exists(itype, key) {
                     let path = itype.path();
                     let msg = if path.is_empty() {
@@ -792,11 +1336,24 @@ impl Everything {
                     } else {
                         format!("{itype} {key} not defined in {path}")
                     };
-                    report(ErrorKey::MissingItem, itype.severity().at_most(max_sev))
-                        .conf(itype.confidence())
-                        .msg(msg)
-                        .loc(token)
-                        .push();
+                    let similar = if will_maybe_log(token, ErrorKey::MissingItem) {
+                        self.database.similar_items(itype, key)
+                    } else {
+                        Vec::new()
+                    };
+                    let mut builder =
+                        report(ErrorKey::MissingItem, itype.severity().at_most(max_sev))
+                            .conf(itype.confidence())
+                            .msg(msg)
+                            .opt_info(did_you_mean(&similar))
+                            .loc(token);
+                    // Suggestions replace the token, so only make them if it is the key itself.
+                    if key == token.as_str() {
+                        for item in similar {
+                            builder = builder.suggest(token, item);
+                        }
+                    }
+                    builder.push();
                 }
             }
         }
@@ -806,6 +1363,17 @@ impl Everything {
         self.verify_exists_implied_max_sev(itype, key, token, Severity::Error);
     }
 
+    /// Verify that the localization key exists in the given language.
+    pub(crate) fn verify_exists_lang(&self, token: &Token, lang: &'static str) {
+        self.verify_exists_implied_lang(token.as_str(), token, lang);
+    }
+
+    /// Like [`Everything::verify_exists_lang`], but for a key that's derived from `token`.
+    pub(crate) fn verify_exists_implied_lang(&self, key: &str, token: &Token, lang: &'static str) {
+        self.add_reference(Item::Localization, key, token.loc);
+        self.localization.verify_exists_implied_lang(key, token, lang);
+    }
+
     #[cfg(feature = "ck3")]
     pub(crate) fn verify_icon(&self, define: &str, token: &Token, suffix: &str) {
         if let Some(icon_path) = self.get_defined_string_warn(token, define) {
@@ -861,6 +1429,7 @@ impl Everything {
         #[cfg(feature = "ck3")]
         if Game::is_ck3() {
             if let Some(trigger) = self.triggers.get(key.as_str()) {
+                self.mark_referenced(Item::ScriptedTrigger, key);
                 return Some(trigger);
             }
             if let Some(trigger) = self.events.get_trigger(key) {
@@ -868,6 +1437,7 @@ impl Everything {
             }
             return None;
         }
+        self.mark_referenced(Item::ScriptedTrigger, key);
         self.triggers.get(key.as_str())
     }
 
@@ -875,6 +1445,7 @@ impl Everything {
         #[cfg(feature = "ck3")]
         if Game::is_ck3() {
             if let Some(effect) = self.effects.get(key.as_str()) {
+                self.mark_referenced(Item::ScriptedEffect, key);
                 return Some(effect);
             }
             if let Some(effect) = self.events.get_effect(key) {
@@ -882,6 +1453,7 @@ impl Everything {
             }
             return None;
         }
+        self.mark_referenced(Item::ScriptedEffect, key);
         self.effects.get(key.as_str())
     }
 
@@ -1009,7 +1581,7 @@ impl Everything {
                 #[cfg(feature = "imperator")]
                 Game::Imperator => &crate::imperator::tables::sounds::SOUNDS_SET,
             };
-            sounds_set.contains(&Lowercase::new(name))
+            sounds_set.contains(&Lowercase::new(name)) || self.sound_banks.exists(name)
         }
     }
 }
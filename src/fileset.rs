@@ -3,18 +3,21 @@
 use std::cmp::Ordering;
 use std::ffi::OsStr;
 use std::fmt::{Display, Formatter};
+use std::fs::{read, read_to_string};
 use std::path::{Path, PathBuf};
 use std::string::ToString;
 use std::sync::RwLock;
 
 use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use walkdir::WalkDir;
 
 use crate::block::Block;
 use crate::everything::{Everything, FilesError};
 use crate::game::Game;
-use crate::helpers::TigerHashSet;
+use crate::game_version::VersionRange;
+use crate::helpers::{TigerHashMap, TigerHashSet};
 use crate::item::Item;
 #[cfg(feature = "vic3")]
 use crate::mod_metadata::ModMetadata;
@@ -28,6 +31,56 @@ use crate::report::{
 };
 use crate::token::Token;
 
+/// File contents that take the place of what's on disk, keyed by full filesystem path.
+///
+/// This is used by editor integrations to validate unsaved buffers.
+static FILE_OVERRIDES: Lazy<RwLock<TigerHashMap<PathBuf, String>>> =
+    Lazy::new(|| RwLock::new(TigerHashMap::default()));
+
+/// Make all future reads of the file at `fullpath` return `contents` instead of what's on disk.
+/// Passing `None` removes the override.
+///
+/// The override only takes effect for files that are (re)loaded after this call.
+///
+/// Paths are compared in canonical form, so `fullpath` does not have to be spelled the same way
+/// as the paths in the [`Fileset`].
+#[allow(clippy::missing_panics_doc)] // only panics on poisoned lock
+pub fn set_file_override(fullpath: &Path, contents: Option<String>) {
+    let fullpath = fullpath.canonicalize().unwrap_or_else(|_| fullpath.to_path_buf());
+    let mut overrides = FILE_OVERRIDES.write().unwrap();
+    if let Some(contents) = contents {
+        overrides.insert(fullpath, contents);
+    } else {
+        overrides.remove(&fullpath);
+    }
+}
+
+/// Return the override for the file at `fullpath`, if there is one.
+fn get_file_override(fullpath: &Path) -> Option<String> {
+    let overrides = FILE_OVERRIDES.read().unwrap();
+    // Checking for empty first avoids canonicalizing every path in the normal case.
+    if overrides.is_empty() {
+        return None;
+    }
+    overrides.get(&fullpath.canonicalize().ok()?).cloned()
+}
+
+/// Read a file as UTF-8 text, respecting any override set with [`set_file_override`].
+pub(crate) fn read_file_to_string(fullpath: &Path) -> std::io::Result<String> {
+    if let Some(contents) = get_file_override(fullpath) {
+        return Ok(contents);
+    }
+    read_to_string(fullpath)
+}
+
+/// Read a file as raw bytes, respecting any override set with [`set_file_override`].
+pub(crate) fn read_file_bytes(fullpath: &Path) -> std::io::Result<Vec<u8>> {
+    if let Some(contents) = get_file_override(fullpath) {
+        return Ok(contents.into_bytes());
+    }
+    read(fullpath)
+}
+
 /// Note that ordering of these enum values matters.
 /// Files later in the order will override files of the same name before them,
 /// and the warnings about duplicates take that into account.
@@ -148,7 +201,15 @@ pub trait FileHandler<T: Send>: Sync + Send {
     /// relative to the mod or vanilla root.
     fn subpath(&self) -> PathBuf;
 
-    /// This is called for each matching file, in arbitrary order.
+    /// Whether this handler loads `entry`, which is a file under [`FileHandler::subpath`].
+    /// This must not have side effects, because it's also used to find the handlers that need
+    /// reloading when a file changes. The default is to load all `.txt` files.
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.filename().to_string_lossy().ends_with(".txt")
+    }
+
+    /// This is called for each file that the handler [handles](FileHandler::handles),
+    /// in arbitrary order.
     /// If a `T` is returned, it will be passed to `handle_file` later.
     /// Since `load_file` is executed multi-threaded while `handle_file`
     /// is single-threaded, try to do the heavy work in this function.
@@ -267,6 +328,13 @@ impl Fileset {
         }
     }
 
+    /// Return the root directories of the mod being analyzed and of the other loaded mods.
+    pub fn mod_roots(&self) -> Vec<&Path> {
+        let mut roots: Vec<&Path> = self.loaded_mods.iter().map(LoadedMod::root).collect();
+        roots.push(self.the_mod.root());
+        roots
+    }
+
     pub fn config(&mut self, config: Block) -> Result<()> {
         for block in config.get_field_blocks("load_mod") {
             let mod_idx;
@@ -447,13 +515,28 @@ impl Fileset {
         }
     }
 
-    pub fn get_files_under<'a>(&'a self, subpath: &'a Path) -> &'a [FileEntry] {
+    pub fn get_files_under<'a>(&'a self, subpath: &Path) -> &'a [FileEntry] {
         let start = self.ordered_files.partition_point(|entry| entry.path < subpath);
         let end = start
             + self.ordered_files[start..].partition_point(|entry| entry.path.starts_with(subpath));
         &self.ordered_files[start..end]
     }
 
+    /// Return the file entry for the file at `fullpath`, if it is one of the files the game
+    /// would load. Files overridden by other files of the same name are not returned.
+    ///
+    /// If no entry has exactly this `fullpath`, the paths are compared in canonical form.
+    pub fn get_entry_by_fullpath(&self, fullpath: &Path) -> Option<&FileEntry> {
+        self.ordered_files.iter().find(|entry| entry.fullpath == fullpath).or_else(|| {
+            let filename = fullpath.file_name()?;
+            let canonical = fullpath.canonicalize().ok()?;
+            self.ordered_files.iter().find(|entry| {
+                entry.filename() == filename
+                    && entry.fullpath.canonicalize().is_ok_and(|path| path == canonical)
+            })
+        })
+    }
+
     pub fn filter_map_under<F, T>(&self, subpath: &Path, f: F) -> Vec<T>
     where
         F: Fn(&FileEntry) -> Option<T> + Sync + Send,
@@ -468,6 +551,9 @@ impl Fileset {
         }
         let subpath = handler.subpath();
         let entries = self.filter_map_under(&subpath, |entry| {
+            if !handler.handles(entry) {
+                return None;
+            }
             handler.load_file(entry, parser).map(|loaded| (entry.clone(), loaded))
         });
         for (entry, loaded) in entries {
@@ -627,8 +713,10 @@ impl Fileset {
                 let msg = "file should be in common/on_actions/";
                 err(ErrorKey::Filename).msg(msg).loc(entry).push();
             } else if Game::is_vic3() && entry.path.starts_with("common/modifiers") {
-                let msg = "file should be in common/static_modifiers since 1.7";
-                err(ErrorKey::Filename).msg(msg).loc(entry).push();
+                if VersionRange::since("1.7").includes_installed() {
+                    let msg = "file should be in common/static_modifiers since 1.7";
+                    err(ErrorKey::Filename).msg(msg).loc(entry).push();
+                }
             } else {
                 let msg = format!("file in unexpected directory `{}`", dirname.display());
                 err(ErrorKey::Filename).msg(msg).loc(entry).push();
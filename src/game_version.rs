@@ -0,0 +1,193 @@
+//! Detection of the installed game's version, so that checks that depend on the game version can
+//! be adjusted to it.
+//!
+//! Validators can tag their checks with a [`VersionRange`], and the checks then only apply when
+//! the installed game is in that range. When the installed version can't be determined,
+//! everything applies, as if the game were the version this validator was made for.
+//!
+//! In the trigger and effect tables, only the `Removed` entries are tied to a version. The tables
+//! can't say that something was added in some version, so newer triggers and effects are still
+//! accepted when an older game is installed.
+
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+
+use crate::fileset::FileKind;
+use crate::report::{warn, Confidence, ErrorKey};
+use crate::token::Loc;
+
+/// The version of the game this validator was made for.
+static SUPPORTED: OnceCell<GameVersion> = OnceCell::new();
+/// The version of the game that's installed, if it could be determined.
+static INSTALLED: OnceCell<GameVersion> = OnceCell::new();
+
+/// The files that the game version can be read from, relative to the game directory.
+const VERSION_FILES: &[&str] = &["launcher/launcher-settings.json", "rawversion"];
+
+/// A game version, such as 1.14.2.1. Missing parts count as 0, so "1.9" is the same as "1.9.0.0".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GameVersion([u32; 4]);
+
+impl GameVersion {
+    /// Parse the leading dotted numbers of a version string. Anything after them, such as a
+    /// version name or build number, is ignored.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches(['v', 'V']);
+        let mut parts = [0; 4];
+        let mut count = 0;
+        for part in s.split('.') {
+            let digits = part.find(|c: char| !c.is_ascii_digit()).unwrap_or(part.len());
+            if digits == 0 || count == parts.len() {
+                break;
+            }
+            parts[count] = part[..digits].parse().ok()?;
+            count += 1;
+            if digits < part.len() {
+                break;
+            }
+        }
+        (count > 0).then_some(GameVersion(parts))
+    }
+
+    fn major_minor(self) -> (u32, u32) {
+        (self.0[0], self.0[1])
+    }
+}
+
+impl Display for GameVersion {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let [major, minor, patch, build] = self.0;
+        write!(f, "{major}.{minor}.{patch}")?;
+        if build != 0 {
+            write!(f, ".{build}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The range of game versions that a table entry or check applies to.
+/// `since` is inclusive and `until` is exclusive.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct VersionRange {
+    since: Option<&'static str>,
+    until: Option<&'static str>,
+}
+
+impl VersionRange {
+    /// Applies from this version on.
+    pub(crate) const fn since(version: &'static str) -> Self {
+        VersionRange { since: Some(version), until: None }
+    }
+
+    /// Applies before this version, for example to things that were removed in it.
+    pub(crate) const fn until(version: &'static str) -> Self {
+        VersionRange { since: None, until: Some(version) }
+    }
+
+    /// Whether the installed game version is in this range. True if the version is unknown.
+    /// Bounds that aren't valid versions are ignored.
+    pub(crate) fn includes_installed(self) -> bool {
+        let Some(installed) = installed_game_version() else {
+            return true;
+        };
+        let parse = |version: Option<&str>| version.and_then(GameVersion::parse);
+        parse(self.since).map_or(true, |since| installed >= since)
+            && parse(self.until).map_or(true, |until| installed < until)
+    }
+}
+
+/// Record the version of the game this validator was made for, such as "1.14.2.1 (Traverse)".
+pub fn set_supported_game_version(version: &str) {
+    if let Some(version) = GameVersion::parse(version) {
+        _ = SUPPORTED.set(version);
+    }
+}
+
+/// Return the installed game version, if it has been detected.
+pub(crate) fn installed_game_version() -> Option<GameVersion> {
+    INSTALLED.get().copied()
+}
+
+/// Whether the installed game is a newer major or minor version than the validator was made for.
+/// Things the validator doesn't know about may well be valid in that case.
+pub(crate) fn installed_is_newer() -> bool {
+    match (installed_game_version(), SUPPORTED.get()) {
+        (Some(installed), Some(supported)) => installed.major_minor() > supported.major_minor(),
+        _ => false,
+    }
+}
+
+/// The confidence to give reports about unknown effects, triggers, and the like.
+pub(crate) fn unknown_token_confidence() -> Confidence {
+    if installed_is_newer() {
+        Confidence::Weak
+    } else {
+        Confidence::Reasonable
+    }
+}
+
+/// Read the installed game's version from the files in `game_dir`, and report if it's not the
+/// version this validator was made for.
+pub(crate) fn detect_game_version(game_dir: &Path) {
+    let Some((installed, pathname, fullpath)) = read_game_version(game_dir) else {
+        return;
+    };
+    _ = INSTALLED.set(installed);
+    let Some(&supported) = SUPPORTED.get() else {
+        return;
+    };
+    if installed.major_minor() == supported.major_minor() {
+        return;
+    }
+    let msg = format!(
+        "the installed game is version {installed}, but this validator was made for version {supported}"
+    );
+    let info = if installed > supported {
+        "new effects, triggers, and other items will be reported as unknown; those reports are marked as weak. Check for a newer release of tiger, or see the --script-docs option"
+    } else {
+        "items that were removed in newer versions will not be reported as removed, but other reports may be about things that are only in newer versions"
+    };
+    let loc = Loc::for_file(pathname, FileKind::Vanilla, fullpath);
+    warn(ErrorKey::Config).msg(msg).info(info).loc(loc).push();
+}
+
+fn read_game_version(game_dir: &Path) -> Option<(GameVersion, PathBuf, PathBuf)> {
+    for pathname in VERSION_FILES {
+        let fullpath = game_dir.join(pathname);
+        let Ok(contents) = read_to_string(&fullpath) else {
+            continue;
+        };
+        let version = if Path::new(pathname).extension().is_some_and(|ext| ext == "json") {
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(&contents) else {
+                continue;
+            };
+            json.get("rawVersion")
+                .or_else(|| json.get("version"))
+                .and_then(serde_json::Value::as_str)
+                .and_then(GameVersion::parse)
+        } else {
+            GameVersion::parse(&contents)
+        };
+        if let Some(version) = version {
+            return Some((version, PathBuf::from(pathname), fullpath));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(GameVersion::parse("1.14.2.1 (Traverse)"), Some(GameVersion([1, 14, 2, 1])));
+        assert_eq!(GameVersion::parse("v1.9"), Some(GameVersion([1, 9, 0, 0])));
+        assert_eq!(GameVersion::parse("1.12.4\n"), Some(GameVersion([1, 12, 4, 0])));
+        assert_eq!(GameVersion::parse("Traverse"), None);
+        assert!(GameVersion::parse("1.9").unwrap() > GameVersion::parse("1.8.1").unwrap());
+    }
+}
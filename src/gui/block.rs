@@ -7,7 +7,7 @@ use crate::gui::validate::validate_property;
 use crate::gui::{BuiltinWidget, GuiValidation, PropertyContainer, WidgetProperty};
 use crate::helpers::{TigerHashMap, TigerHashSet};
 use crate::lowercase::Lowercase;
-use crate::report::{err, untidy, warn, ErrorKey};
+use crate::report::{err, untidy, warn, will_maybe_log, ErrorKey};
 use crate::token::Token;
 
 /// An element of a [`GuiBlock`]
@@ -92,6 +92,7 @@ impl GuiBlock {
             GuiBlockFrom::Template | GuiBlockFrom::NoParent => (),
             GuiBlockFrom::WidgetKey(base) | GuiBlockFrom::TypeBase(base) => {
                 if let Some(basetype) = types.get(&Lowercase::new(base.as_str())) {
+                    basetype.mark_used();
                     gui.container = basetype.builtin(types).map(PropertyContainer::from);
                     let gui_block = basetype.gui_block(types, templates);
                     gui.substnames.clone_from(&gui_block.substnames);
@@ -103,6 +104,7 @@ impl GuiBlock {
             }
             GuiBlockFrom::TypeWrapper(base) => {
                 if let Some(basetype) = types.get(&Lowercase::new(base.as_str())) {
+                    basetype.mark_used();
                     gui.container = basetype.builtin(types).map(PropertyContainer::from);
                 }
             }
@@ -128,6 +130,7 @@ impl GuiBlock {
                         } else if key_lc == "using" {
                             if let Some(value) = bv.expect_value() {
                                 if let Some(template) = templates.get(value.as_str()) {
+                                    template.mark_used();
                                     gui.inline(&template.gui_block(types, templates));
                                 } else {
                                     untidy(ErrorKey::Gui).msg("template not found").loc(key).push();
@@ -196,7 +199,13 @@ impl GuiBlock {
                             err(ErrorKey::WrongGame).weak().msg(msg).loc(key).push();
                         } else {
                             let msg = format!("unknown gui field `{key}`");
-                            err(ErrorKey::UnknownField).weak().msg(msg).loc(key).push();
+                            let mut builder = err(ErrorKey::UnknownField).weak().msg(msg).loc(key);
+                            if will_maybe_log(key, ErrorKey::UnknownField) {
+                                for similar in WidgetProperty::similar(key.as_str()) {
+                                    builder = builder.suggest(key, similar);
+                                }
+                            }
+                            builder.push();
                         }
                     } else if let Some(key) = item.expect_value() {
                         let key_lc = Lowercase::new(key.as_str());
@@ -11,6 +11,7 @@ use crate::game::GameFlags;
 use crate::gui::BuiltinWidget;
 #[cfg(doc)]
 use crate::gui::GuiBlock;
+use crate::helpers::similar_names;
 use crate::item::Item;
 use crate::lowercase::Lowercase;
 
@@ -848,6 +849,20 @@ impl WidgetProperty {
             _ => GameFlags::all(),
         }
     }
+
+    /// Return the names of the properties for the current game that are spelled similarly to
+    /// `key`.
+    pub fn similar(key: &str) -> Vec<&'static str> {
+        let game = GameFlags::game();
+        let names = WidgetProperty::VARIANTS
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| {
+                WidgetProperty::from_repr(i).is_some_and(|prop| prop.to_game_flags().contains(game))
+            })
+            .map(|(_, &prop)| prop);
+        similar_names(key, names)
+    }
 }
 
 /// The container type of a [`GuiBlock`], which determines which properties are accepted.
@@ -28,7 +28,7 @@ pub fn validate_property(
     if !gameflags.contains(game) {
         if property == WidgetProperty::tooltip_enabled {
             let msg = "tooltip_enabled has been renamed to tooltip_visible";
-            err(ErrorKey::Removed).msg(msg).loc(key).push();
+            err(ErrorKey::Removed).msg(msg).loc(key).fix(key, "tooltip_visible").push();
         } else {
             let msg = format!("{key} is only for {gameflags}");
             err(ErrorKey::WrongGame).weak().msg(msg).loc(key).push();
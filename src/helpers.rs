@@ -90,6 +90,47 @@ pub fn stringify_list(v: &[&str]) -> String {
     format!("{}", Choices::AndChoices(v))
 }
 
+/// The most names that [`similar_names`] returns.
+const MAX_SIMILAR_NAMES: usize = 3;
+
+/// Return the names from `candidates` that are closest to `name`, ignoring case, best first.
+/// Names that are too different to be a likely typo are left out.
+///
+/// This is for "did you mean" suggestions when `name` is not known.
+pub fn similar_names<'a, I>(name: &str, candidates: I) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let name_lc = name.to_lowercase();
+    let len = name_lc.chars().count();
+    // Allow about one typo per three characters. Swapped letters count as one typo.
+    #[allow(clippy::integer_division)]
+    let max_distance = (len / 3).max(1);
+    let mut found: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter(|&candidate| {
+            candidate != name && candidate.chars().count().abs_diff(len) <= max_distance
+        })
+        .filter_map(|candidate| {
+            let distance = strsim::osa_distance(&name_lc, &candidate.to_lowercase());
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+    found.sort_unstable();
+    found.dedup();
+    found.into_iter().take(MAX_SIMILAR_NAMES).map(|(_, candidate)| candidate).collect()
+}
+
+/// Return a "did you mean" hint listing the `names`, if there are any.
+pub fn did_you_mean(names: &[&str]) -> Option<String> {
+    if names.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = names.iter().map(|name| format!("`{name}`")).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    Some(format!("did you mean {}?", stringify_choices(&names)))
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TriBool {
     True,
@@ -1,11 +1,12 @@
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use image::{DynamicImage, Rgb};
+use image::{DynamicImage, Rgb, RgbImage};
 
 use crate::block::Block;
+use crate::data::province_map::ProvinceMap;
 use crate::everything::Everything;
-use crate::fileset::{FileEntry, FileHandler};
+use crate::fileset::{FileEntry, FileHandler, FileKind};
 use crate::helpers::{TigerHashMap, TigerHashSet};
 use crate::item::Item;
 use crate::parse::csv::{parse_csv, read_csv};
@@ -29,6 +30,12 @@ pub struct ImperatorProvinces {
     /// Kept and used for error reporting.
     definition_csv: Option<FileEntry>,
 
+    /// Kept and used for error reporting.
+    adjacencies_csv: Option<FileEntry>,
+
+    /// The provinces.png, kept until `finalize` to check the geometry of the map.
+    provinces_png: Option<(FileEntry, RgbImage)>,
+
     adjacencies: Vec<Adjacency>,
 
     impassable: TigerHashSet<ProvId>,
@@ -155,6 +162,41 @@ impl ImperatorProvinces {
         self.provinces.values().map(|item| &item.key)
     }
 
+    /// Check the geometry of the map, if the mod changes any of the map files.
+    /// The base game's map is assumed to be fine, and checking a full-size map takes a while.
+    fn check_map(&mut self, ids: &TigerHashMap<Rgb<u8>, ProvId>) {
+        let Some((provinces_png, img)) = self.provinces_png.take() else {
+            return;
+        };
+        let is_mod = |entry: Option<&FileEntry>| entry.is_some_and(|e| e.kind() == FileKind::Mod);
+        if !is_mod(Some(&provinces_png))
+            && !is_mod(self.definition_csv.as_ref())
+            && !is_mod(self.adjacencies_csv.as_ref())
+        {
+            return;
+        }
+
+        let map = ProvinceMap::new(img);
+        map.check_areas(&provinces_png, |color| ids.get(&color).map(|id| format!("province {id}")));
+
+        let mut ids: Vec<ProvId> = ids.values().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            let province = &self.provinces[&id];
+            if map.area(province.color).is_none() {
+                let msg = format!("the color of province {id} does not appear in provinces.png");
+                err(ErrorKey::Colors)
+                    .msg(msg)
+                    .loc(&province.key)
+                    .loc_msg(&provinces_png, "map")
+                    .push();
+            }
+        }
+        for item in &self.adjacencies {
+            item.check_coords(&map, self);
+        }
+    }
+
     pub fn validate(&self, _data: &Everything) {
         for item in &self.adjacencies {
             item.validate(self);
@@ -175,6 +217,14 @@ impl FileHandler<FileContent> for ImperatorProvinces {
         PathBuf::from("map_data")
     }
 
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.path().components().count() == 2
+            && matches!(
+                &*entry.filename().to_string_lossy(),
+                "adjacencies.csv" | "definition.csv" | "provinces.png" | "default.map"
+            )
+    }
+
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<FileContent> {
         if entry.path().components().count() == 2 {
             match &*entry.filename().to_string_lossy() {
@@ -237,6 +287,7 @@ impl FileHandler<FileContent> for ImperatorProvinces {
     fn handle_file(&mut self, entry: &FileEntry, content: FileContent) {
         match content {
             FileContent::Adjacencies(content) => {
+                self.adjacencies_csv = Some(entry.clone());
                 let mut seen_terminator = false;
                 for csv in parse_csv(entry, 1, &content) {
                     if csv[0].is("-1") {
@@ -265,6 +316,7 @@ impl FileHandler<FileContent> for ImperatorProvinces {
                     for pixel in img.pixels() {
                         self.colors.insert(*pixel);
                     }
+                    self.provinces_png = Some((entry.clone(), img));
                 }
             }
             FileContent::DefaultMap(block) => self.load_impassable(&block),
@@ -305,10 +357,10 @@ impl FileHandler<FileContent> for ImperatorProvinces {
                 untidy(ErrorKey::Colors).msg(msg).loc(definition_csv).push();
             }
         }
+        self.check_map(&seen_colors);
     }
 }
 
-#[allow(dead_code)] // TODO
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Coords {
     x: i32,
@@ -325,8 +377,7 @@ pub struct Adjacency {
     /// sea or `river_large`
     kind: Token,
     through: ProvId,
-    /// TODO: check start and stop are map coordinates and have the right color on province.png
-    /// They can be -1 -1 though.
+    /// Map coordinates on the `from` and `to` provinces, or -1 -1.
     start: Coords,
     stop: Coords,
     comment: Token,
@@ -382,6 +433,32 @@ impl Adjacency {
             }
         }
     }
+
+    /// Check that the start and stop coordinates are on the `from` and `to` provinces.
+    /// Like other map positions, the y coordinate counts from the bottom of the map.
+    fn check_coords(&self, map: &ProvinceMap, provinces: &ImperatorProvinces) {
+        for (coords, provid, which) in
+            [(self.start, self.from, "start"), (self.stop, self.to, "stop")]
+        {
+            if coords.x == -1 && coords.y == -1 {
+                continue;
+            }
+            // Unknown provinces are reported in `validate`.
+            let Some(province) = provinces.provinces.get(&provid) else {
+                continue;
+            };
+            let (x, y) = (i64::from(coords.x), i64::from(coords.y));
+            let msg = match map.color_at(x, y) {
+                None => format!("{which} coordinates ({x}, {y}) are outside the map"),
+                Some(color) if color != province.color => {
+                    format!("{which} coordinates ({x}, {y}) are not on province {provid}")
+                }
+                Some(_) => continue,
+            };
+            let info = "they should be a pixel of the province's color in provinces.png, counting y from the bottom, or -1 -1";
+            warn(ErrorKey::Validation).msg(msg).info(info).loc(self.line).push();
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
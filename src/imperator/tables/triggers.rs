@@ -1,7 +1,7 @@
 use once_cell::sync::Lazy;
 
 use crate::everything::Everything;
-use crate::helpers::TigerHashMap;
+use crate::helpers::{similar_names, TigerHashMap};
 use crate::item::Item;
 use crate::report::{warn, ErrorKey};
 use crate::scopes::Scopes;
@@ -49,6 +49,11 @@ pub fn scope_trigger(name: &Token, data: &Everything) -> Option<(Scopes, Trigger
     None
 }
 
+/// Return the names of the triggers that are spelled similarly to `name`.
+pub fn similar_triggers(name: &str) -> Vec<&'static str> {
+    similar_names(name, TRIGGER.iter().map(|&(_, trigger, _)| trigger))
+}
+
 static TRIGGER_MAP: Lazy<TigerHashMap<&'static str, (Scopes, Trigger)>> = Lazy::new(|| {
     let mut hash = TigerHashMap::default();
     for (from, s, trigger) in TRIGGER {
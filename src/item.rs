@@ -1466,6 +1466,28 @@ impl Item {
             _ => Severity::Error,
         }
     }
+
+    /// Return true iff items of this type do nothing unless they are referred to from script or
+    /// from the gui files. These are the items that `--unused` reports.
+    ///
+    /// Most other item types are used by the game engine directly, for example decisions and
+    /// character interactions, so they can't be reported as unused.
+    pub fn needs_reference(self) -> bool {
+        matches!(
+            self,
+            Item::EffectLocalization
+                | Item::Event
+                | Item::GuiTemplate
+                | Item::GuiType
+                | Item::OnAction
+                | Item::ScriptedEffect
+                | Item::ScriptedList
+                | Item::ScriptedModifier
+                | Item::ScriptedTrigger
+                | Item::ScriptValue
+                | Item::TriggerLocalization
+        )
+    }
 }
 
 /// The callback type for adding one item instance to the database.
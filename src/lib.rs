@@ -9,19 +9,24 @@ compile_error!("features \"ck3\", \"vic3\", and \"imperator\" cannot be enabled
 compile_error!("exactly one of the features \"ck3\", \"vic3\", \"imperator\" must be enabled");
 
 pub use crate::config_load::validate_config_file;
-pub use crate::everything::Everything;
-pub use crate::fileset::FileKind;
+pub use crate::data_types_log::{data_types_entries, DataTypesEntry, DefinitionType};
+pub use crate::everything::{Changes, Everything};
+pub use crate::fileset::{set_file_override, FileKind};
 pub use crate::game::Game;
+pub use crate::game_version::set_supported_game_version;
 pub use crate::item::Item;
 #[cfg(feature = "vic3")]
 pub use crate::mod_metadata::ModMetadata;
 #[cfg(any(feature = "ck3", feature = "imperator"))]
 pub use crate::modfile::ModFile;
 pub use crate::report::{
-    add_loaded_mod_root, disable_ansi_colors, emit_reports, log, set_output_file, set_output_style,
-    set_show_loaded_mods, set_show_vanilla, suppress_from_json, take_reports, Confidence,
-    LogReport, PointedMessage, Severity,
+    add_loaded_mod_root, apply_fixes, baseline_from_json, baseline_stale_entries,
+    disable_ansi_colors, emit_report_list, emit_reports, log, report_fingerprint, set_output_file,
+    set_output_sarif, set_output_style, set_show_loaded_mods, set_show_vanilla, suppress_from_json,
+    take_reports, warn_unused_ignores, write_baseline_json, Confidence, Fingerprint, Fix, FixKind,
+    FixSummary, LogReport, PointedMessage, Severity,
 };
+pub use crate::script_docs::load_script_docs;
 pub use crate::token::{Loc, Token};
 
 #[cfg(feature = "ck3")]
@@ -35,6 +40,7 @@ mod block;
 mod config_load;
 mod context;
 mod data;
+mod data_types_log;
 mod datatype;
 mod date;
 mod db;
@@ -42,14 +48,18 @@ mod dds;
 mod desc;
 mod effect;
 mod effect_validation;
+mod event_graph;
 mod everything;
 mod fileset;
 mod game;
+mod game_version;
 mod gui;
 mod helpers;
 mod item;
+mod loca_report;
 mod lowercase;
 mod macros;
+mod map_images;
 #[cfg(feature = "vic3")]
 mod mod_metadata;
 #[cfg(any(feature = "ck3", feature = "imperator"))]
@@ -59,10 +69,13 @@ mod on_action;
 mod parse;
 mod pathtable;
 mod pdxfile;
+mod references;
 mod report;
 mod rivers;
 mod scopes;
+mod script_docs;
 mod script_value;
+mod sound_banks;
 mod token;
 mod tooltipped;
 mod trigger;
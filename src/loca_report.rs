@@ -0,0 +1,278 @@
+//! Reports of how complete the mod's translations are, for translators to see what needs doing.
+//!
+//! This is a table of the state of each language per localization file, and a snapshot of the
+//! localization texts that later runs can compare against to find out-of-date translations.
+
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::data::localization::{LocaEntry, Localization, REFERENCE_LANGUAGE};
+use crate::fileset::FileKind;
+use crate::helpers::TigerHashMap;
+use crate::report::{warn, ErrorKey};
+
+/// The state of the translations of one reference language file into one other language.
+#[derive(Debug, Default)]
+struct FileLanguage {
+    translated: usize,
+    /// Keys that have no translation at all.
+    missing: Vec<String>,
+    /// Keys whose translation has a lower version number (the `:1` in `key:1`) than the original.
+    stale: Vec<String>,
+    /// Keys whose translation is exactly the same as the original.
+    identical: Vec<String>,
+}
+
+impl FileLanguage {
+    fn total(&self) -> usize {
+        self.translated + self.missing.len() + self.stale.len() + self.identical.len()
+    }
+
+    fn percent_complete(&self) -> f64 {
+        percent(self.translated, self.total())
+    }
+}
+
+/// Return `part` as a percentage of `total`, rounded to one decimal.
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    // The counts are far too small to lose precision.
+    #[allow(clippy::cast_precision_loss)]
+    let percent = part as f64 * 100.0 / total as f64;
+    (percent * 10.0).round() / 10.0
+}
+
+/// For each file: its path, its number of keys, and the state of each language.
+type Matrix = Vec<(PathBuf, usize, Vec<(&'static str, FileLanguage)>)>;
+
+/// Compare each of the mod's reference language keys with the other checked languages.
+fn build(loca: &Localization) -> Matrix {
+    let langs: Vec<&'static str> =
+        loca.check_langs().iter().copied().filter(|&lang| lang != REFERENCE_LANGUAGE).collect();
+    let mut files: TigerHashMap<PathBuf, Vec<_>> = TigerHashMap::default();
+    for entry in loca.iter_lang(REFERENCE_LANGUAGE) {
+        if entry.key().loc.kind == FileKind::Mod {
+            files.entry(entry.key().loc.pathname().to_path_buf()).or_default().push(entry);
+        }
+    }
+
+    let mut matrix: Matrix = files
+        .into_iter()
+        .map(|(path, mut entries)| {
+            entries.sort_unstable_by_key(|entry| entry.key().loc);
+            let row = langs
+                .iter()
+                .map(|&lang| {
+                    let mut state = FileLanguage::default();
+                    for entry in &entries {
+                        let key = entry.key().to_string();
+                        match loca.get_lang(entry.key().as_str(), lang) {
+                            None => state.missing.push(key),
+                            Some(tr) if tr.version() < entry.version() => state.stale.push(key),
+                            Some(tr) if same_text(tr, entry) => state.identical.push(key),
+                            Some(_) => state.translated += 1,
+                        }
+                    }
+                    (lang, state)
+                })
+                .collect();
+            (path, entries.len(), row)
+        })
+        .collect();
+    matrix.sort_unstable_by(|(a, _, _), (b, _, _)| a.cmp(b));
+    matrix
+}
+
+/// Whether the translation is the same non-empty text as the original.
+fn same_text(tr: &LocaEntry, orig: &LocaEntry) -> bool {
+    match (tr.orig(), orig.orig()) {
+        (Some(tr), Some(orig)) => !orig.as_str().is_empty() && tr.is(orig.as_str()),
+        _ => false,
+    }
+}
+
+/// Write the report as CSV, with one row per file and language.
+/// The key lists are separated by spaces.
+pub(crate) fn write_csv(loca: &Localization, fullpath: &Path) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(fullpath)?);
+    writeln!(
+        writer,
+        "file,language,keys,translated,missing,stale,identical,percent_complete,missing_keys,stale_keys,identical_keys"
+    )?;
+    for (path, _, row) in build(loca) {
+        for (lang, state) in row {
+            writeln!(
+                writer,
+                "{},{lang},{},{},{},{},{},{},{},{},{}",
+                csv_field(&path.to_string_lossy()),
+                state.total(),
+                state.translated,
+                state.missing.len(),
+                state.stale.len(),
+                state.identical.len(),
+                state.percent_complete(),
+                csv_field(&state.missing.join(" ")),
+                csv_field(&state.stale.join(" ")),
+                csv_field(&state.identical.join(" ")),
+            )?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write the report as a JSON object with a list of files, each with their state per language,
+/// and the totals per language.
+pub(crate) fn write_json(loca: &Localization, fullpath: &Path) -> Result<()> {
+    let matrix = build(loca);
+    let mut totals: Vec<(&str, usize, usize)> = Vec::new();
+    let files: Vec<Value> = matrix
+        .iter()
+        .map(|(path, keys, row)| {
+            let mut languages = serde_json::Map::new();
+            for (lang, state) in row {
+                match totals.iter_mut().find(|(l, _, _)| l == lang) {
+                    Some((_, keys, translated)) => {
+                        *keys += state.total();
+                        *translated += state.translated;
+                    }
+                    None => totals.push((lang, state.total(), state.translated)),
+                }
+                languages.insert(
+                    (*lang).to_owned(),
+                    json!({
+                        "translated": state.translated,
+                        "missing": state.missing,
+                        "stale": state.stale,
+                        "identical": state.identical,
+                        "percent_complete": state.percent_complete(),
+                    }),
+                );
+            }
+            json!({
+                "file": path.to_string_lossy(),
+                "keys": keys,
+                "languages": languages,
+            })
+        })
+        .collect();
+    let totals: serde_json::Map<String, Value> = totals
+        .into_iter()
+        .map(|(lang, keys, translated)| {
+            let total = json!({
+                "keys": keys,
+                "translated": translated,
+                "percent_complete": percent(translated, keys),
+            });
+            (lang.to_owned(), total)
+        })
+        .collect();
+    let writer = BufWriter::new(File::create(fullpath)?);
+    serde_json::to_writer_pretty(
+        writer,
+        &json!({ "reference": REFERENCE_LANGUAGE, "files": files, "totals": totals }),
+    )?;
+    Ok(())
+}
+
+/// Quote a CSV field if needed.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+/// The texts of the mod's localization keys at some point in time, indexed by key and then by
+/// language. Sorted, so that the file can be usefully kept under version control.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    reference: String,
+    keys: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Write the texts of the mod's reference language keys, and of their translations, to a snapshot
+/// file for use with [`check_snapshot`] in later runs.
+///
+/// Returns the number of keys written.
+pub(crate) fn write_snapshot(loca: &Localization, fullpath: &Path) -> Result<usize> {
+    let mut snapshot =
+        Snapshot { reference: REFERENCE_LANGUAGE.to_owned(), keys: BTreeMap::default() };
+    for entry in loca.iter_lang(REFERENCE_LANGUAGE) {
+        if entry.key().loc.kind != FileKind::Mod {
+            continue;
+        }
+        let mut texts = BTreeMap::new();
+        for &lang in loca.check_langs() {
+            if let Some(text) = loca.get_lang(entry.key().as_str(), lang).and_then(LocaEntry::orig)
+            {
+                texts.insert(lang.to_owned(), text.to_string());
+            }
+        }
+        snapshot.keys.insert(entry.key().to_string(), texts);
+    }
+    let writer = BufWriter::new(File::create(fullpath)?);
+    serde_json::to_writer_pretty(writer, &snapshot)?;
+    Ok(snapshot.keys.len())
+}
+
+/// Compare the localization with a snapshot from [`write_snapshot`], and report the translations
+/// that are still the same as in the snapshot while their reference language text has changed.
+///
+/// Returns the number of keys whose reference text changed.
+pub(crate) fn check_snapshot(loca: &Localization, fullpath: &Path) -> Result<usize> {
+    let snapshot: Snapshot = serde_json::from_str(&read_to_string(fullpath)?)?;
+    let Some(reference) =
+        loca.check_langs().iter().copied().find(|&lang| lang == snapshot.reference)
+    else {
+        return Ok(0);
+    };
+    let mut changed = 0;
+    for (key, texts) in &snapshot.keys {
+        let Some(old_text) = texts.get(reference) else {
+            continue;
+        };
+        let Some(entry) = loca.get_lang(key, reference) else {
+            continue;
+        };
+        if entry.orig().is_some_and(|text| text.is(old_text)) {
+            continue;
+        }
+        changed += 1;
+        for &lang in loca.check_langs() {
+            if lang == reference {
+                continue;
+            }
+            let Some(old_translation) = texts.get(lang) else {
+                continue;
+            };
+            let Some(translation) = loca.get_lang(key, lang) else {
+                continue;
+            };
+            if translation.key().loc.kind == FileKind::Mod
+                && translation.orig().is_some_and(|text| text.is(old_translation))
+            {
+                let msg = format!(
+                    "{lang} localization for `{key}` was not updated after the {reference} text changed"
+                );
+                let info = format!("the {reference} text was \"{old_text}\"");
+                warn(ErrorKey::StaleLocalization)
+                    .msg(msg)
+                    .info(info)
+                    .loc(translation.key())
+                    .loc_msg(entry.key(), format!("{reference} text"))
+                    .push();
+            }
+        }
+    }
+    Ok(changed)
+}
@@ -0,0 +1,287 @@
+//! Validator for the images that make up the map, other than `provinces.png` and `rivers.png`
+//! which have their own.
+//!
+//! The heightmap, the terrain masks, and the flat map and color map textures all cover the same
+//! area as `provinces.png`, so their sizes have to agree with it. The game also expects specific
+//! pixel formats for some of them.
+
+use std::fs::File;
+use std::path::Path;
+
+use png::{BitDepth, ColorType, Decoder};
+
+use crate::block::Block;
+use crate::everything::Everything;
+use crate::fileset::FileEntry;
+use crate::game::Game;
+use crate::item::Item;
+use crate::pdxfile::PdxFile;
+use crate::report::{err, warn, ErrorKey};
+use crate::util::SmartJoin;
+
+/// The header information of a PNG file.
+struct PngInfo<'a> {
+    entry: &'a FileEntry,
+    width: u32,
+    height: u32,
+    color_type: ColorType,
+    bit_depth: BitDepth,
+}
+
+impl PngInfo<'_> {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Report if the image isn't 8-bit grayscale.
+    fn expect_grayscale(&self, what: &str) {
+        if self.color_type != ColorType::Grayscale || self.bit_depth != BitDepth::Eight {
+            let msg = format!(
+                "{what} should be 8-bit grayscale, not {}-bit {:?}",
+                self.bit_depth as u8, self.color_type
+            );
+            err(ErrorKey::ImageFormat).msg(msg).loc(self.entry).push();
+        }
+    }
+
+    /// Report if the image isn't the same size as `provinces.png`.
+    fn expect_size(&self, what: &str, provinces: &PngInfo) {
+        if self.size() != provinces.size() {
+            let (width, height) = self.size();
+            let (pwidth, pheight) = provinces.size();
+            let msg =
+                format!("{what} is {width}x{height}, but provinces.png is {pwidth}x{pheight}");
+            err(ErrorKey::ImageFormat)
+                .msg(msg)
+                .loc(self.entry)
+                .loc_msg(provinces.entry, "provinces.png")
+                .push();
+        }
+    }
+}
+
+/// Whether an image of `size` has the same proportions as one of `other` size, so that it can be
+/// stretched over it.
+fn same_aspect(size: (u32, u32), other: (u32, u32)) -> bool {
+    u64::from(size.0) * u64::from(other.1) == u64::from(size.1) * u64::from(other.0)
+}
+
+/// Find the file the game would load from this path.
+fn find_entry<'a>(data: &'a Everything, path: &str) -> Option<&'a FileEntry> {
+    let path = Path::new(path);
+    data.fileset.get_files_under(path).iter().find(|entry| entry.path() == path)
+}
+
+/// Read the header of a PNG file, reporting it if the file can't be read.
+fn read_png_info(entry: &FileEntry) -> Option<PngInfo<'_>> {
+    let result = File::open(entry.fullpath())
+        .map_err(anyhow::Error::from)
+        .and_then(|f| Decoder::new(f).read_info().map_err(anyhow::Error::from));
+    match result {
+        Ok(reader) => {
+            let info = reader.info();
+            Some(PngInfo {
+                entry,
+                width: info.width,
+                height: info.height,
+                color_type: info.color_type,
+                bit_depth: info.bit_depth,
+            })
+        }
+        Err(e) => {
+            err(ErrorKey::ReadError)
+                .msg("could not read image")
+                .info(format!("{e:#}"))
+                .loc(entry)
+                .push();
+            None
+        }
+    }
+}
+
+pub fn validate_map_images(data: &Everything) {
+    let Some(provinces) = find_entry(data, "map_data/provinces.png").and_then(read_png_info) else {
+        return;
+    };
+
+    if let Some(heightmap) = find_entry(data, "map_data/heightmap.png").and_then(read_png_info) {
+        heightmap.expect_grayscale("heightmap.png");
+        heightmap.expect_size("heightmap.png", &provinces);
+    }
+    if let Some(entry) = find_entry(data, "map_data/heightmap.heightmap") {
+        if let Some(block) = PdxFile::read_optional_bom(entry, &data.parser) {
+            validate_heightmap_settings(&block, data, &provinces);
+        }
+    }
+    if let Some(entry) = find_entry(data, "gfx/map/terrain/materials.settings") {
+        if let Some(block) = PdxFile::read_optional_bom(entry, &data.parser) {
+            validate_terrain_masks(&block, data, &provinces);
+        }
+    }
+
+    for path in stretched_textures() {
+        let Some(entry) = find_entry(data, path) else {
+            continue;
+        };
+        let Some(size) = data.dds.get_size(path) else {
+            continue;
+        };
+        if !same_aspect(size, provinces.size()) {
+            let (width, height) = size;
+            let (pwidth, pheight) = provinces.size();
+            let msg = format!(
+                "{} is {width}x{height}, which doesn't have the proportions of provinces.png at {pwidth}x{pheight}",
+                entry.filename().to_string_lossy()
+            );
+            let info =
+                "it is stretched over the whole map, so it will not line up with the provinces";
+            warn(ErrorKey::ImageFormat)
+                .msg(msg)
+                .info(info)
+                .loc(entry)
+                .loc_msg(provinces.entry, "provinces.png")
+                .push();
+        }
+    }
+}
+
+/// The flat map and color map textures that the game stretches over the whole map.
+fn stretched_textures() -> &'static [&'static str] {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => &["gfx/map/terrain/flatmap.dds", "gfx/map/terrain/colormap.dds"],
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => &["gfx/map/textures/flatmap.dds", "gfx/map/terrain/colormap.dds"],
+        #[cfg(feature = "imperator")]
+        Game::Imperator => &["gfx/map/terrain/flatmap.dds", "gfx/map/terrain/colormap.dds"],
+    }
+}
+
+/// Check the sizes in `heightmap.heightmap`, and the packed heightmap and indirection images it
+/// names.
+fn validate_heightmap_settings(block: &Block, data: &Everything, provinces: &PngInfo) {
+    if let Some(size) = block.get_field_list("original_heightmap_size") {
+        let size: Vec<u32> = size.iter().filter_map(|t| t.as_str().parse().ok()).collect();
+        if size.len() == 2 && (size[0], size[1]) != provinces.size() {
+            let (pwidth, pheight) = provinces.size();
+            let msg = format!(
+                "original_heightmap_size is {}x{}, but provinces.png is {pwidth}x{pheight}",
+                size[0], size[1]
+            );
+            err(ErrorKey::ImageFormat)
+                .msg(msg)
+                .loc(block.get_key("original_heightmap_size").unwrap())
+                .loc_msg(provinces.entry, "provinces.png")
+                .push();
+        }
+    }
+
+    if let Some(token) = block.get_field_value("heightmap_file") {
+        data.verify_exists(Item::File, token);
+    }
+    if let Some(token) = block.get_field_value("indirection_file") {
+        data.verify_exists(Item::File, token);
+        let Some(indirection) = find_entry(data, token.as_str()).and_then(read_png_info) else {
+            return;
+        };
+        // Each tile covers `tile_size - 1` pixels of the original heightmap, because tiles share
+        // their edges. The indirection image has one pixel per tile.
+        let Some(tile_size) = block.get_field_integer("tile_size") else {
+            return;
+        };
+        let Ok(tile_pixels) = u32::try_from(tile_size - 1) else {
+            return;
+        };
+        if tile_pixels == 0 {
+            return;
+        }
+        let (pwidth, pheight) = provinces.size();
+        let expected = (pwidth.div_ceil(tile_pixels), pheight.div_ceil(tile_pixels));
+        if indirection.size() != expected {
+            let msg = format!(
+                "{token} is {}x{}, but should be {}x{} for a {pwidth}x{pheight} map with tile_size {tile_size}",
+                indirection.width, indirection.height, expected.0, expected.1
+            );
+            err(ErrorKey::ImageFormat)
+                .msg(msg)
+                .loc(indirection.entry)
+                .loc_msg(token, "indirection_file")
+                .push();
+        }
+    }
+}
+
+/// Collect the terrain materials, which are the blocks with a `mask` field.
+fn collect_materials<'a>(block: &'a Block, materials: &mut Vec<&'a Block>) {
+    if block.has_key("mask") {
+        materials.push(block);
+        return;
+    }
+    for block in block.iter_blocks() {
+        collect_materials(block, materials);
+    }
+    for (_, block) in block.iter_definitions() {
+        collect_materials(block, materials);
+    }
+}
+
+/// Check that every terrain material has its mask, that the masks are 8-bit grayscale, and that
+/// they are all the same size with the same proportions as the map.
+fn validate_terrain_masks(block: &Block, data: &Everything, provinces: &PngInfo) {
+    let mut materials = Vec::new();
+    collect_materials(block, &mut materials);
+
+    let mut first: Option<PngInfo> = None;
+    for material in materials {
+        let Some(token) = material.get_field_value("mask") else {
+            continue;
+        };
+        // In Vic3, masks can also be the keys of the terrain masks defined in json files.
+        #[cfg(feature = "vic3")]
+        if data.item_exists(Item::TerrainMask, token.as_str()) {
+            continue;
+        }
+        let path = token.loc.pathname().smart_join_parent(token.as_str());
+        let path = path.to_string_lossy();
+        let Some(entry) = find_entry(data, &path) else {
+            let msg = format!("terrain mask {path} does not exist");
+            let info = "every terrain material needs a mask that says where it is painted";
+            err(ErrorKey::MissingFile).msg(msg).info(info).loc(token).push();
+            continue;
+        };
+        let Some(mask) = read_png_info(entry) else {
+            continue;
+        };
+        mask.expect_grayscale("terrain mask");
+        match &first {
+            None => {
+                if !same_aspect(mask.size(), provinces.size()) {
+                    let (width, height) = mask.size();
+                    let (pwidth, pheight) = provinces.size();
+                    let msg = format!(
+                        "terrain mask is {width}x{height}, which doesn't have the proportions of provinces.png at {pwidth}x{pheight}"
+                    );
+                    warn(ErrorKey::ImageFormat)
+                        .msg(msg)
+                        .loc(entry)
+                        .loc_msg(provinces.entry, "provinces.png")
+                        .push();
+                }
+                first = Some(mask);
+            }
+            Some(first) if first.size() != mask.size() => {
+                let (width, height) = mask.size();
+                let msg = format!(
+                    "terrain mask is {width}x{height}, but the other masks are {}x{}",
+                    first.width, first.height
+                );
+                err(ErrorKey::ImageFormat)
+                    .msg(msg)
+                    .loc(entry)
+                    .loc_msg(first.entry, "other mask")
+                    .push();
+            }
+            Some(_) => (),
+        }
+    }
+}
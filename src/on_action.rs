@@ -14,6 +14,7 @@ use crate::helpers::TigerHashMap;
 use crate::item::Item;
 use crate::parse::pdxfile::parse_pdx_internal;
 use crate::scopes::Scopes;
+use crate::script_docs;
 use crate::token::Token;
 
 #[derive(Debug, Clone)]
@@ -46,6 +47,9 @@ pub fn on_action_scopecontext(key: &Token, data: &Everything) -> Option<ScopeCon
         }
         return Some(sc);
     }
+    if let Some(root) = script_docs::on_action(key.as_str()) {
+        return Some(ScopeContext::new(root, key));
+    }
 
     #[cfg(feature = "ck3")]
     if Game::is_ck3() {
@@ -134,7 +134,13 @@ impl Parser {
             if loc.column == 1 && !self.stack.is_empty() {
                 let msg = "possible bracket error";
                 let info = "This closing bracket is at the start of a line but does not end a top-level item.";
-                warn(ErrorKey::BracePlacement).msg(msg).info(info).loc(loc).push();
+                let indent = "\t".repeat(self.stack.len());
+                warn(ErrorKey::BracePlacement)
+                    .msg(msg)
+                    .info(info)
+                    .loc(loc)
+                    .suggest(loc, indent)
+                    .push();
             }
         } else {
             err(ErrorKey::ParseError).msg(format!("Unexpected {bracket}")).loc(loc).push();
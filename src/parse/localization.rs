@@ -6,7 +6,7 @@ use crate::data::localization::{LocaEntry, LocaValue, MacroValue};
 use crate::datatype::{Code, CodeArg, CodeChain};
 use crate::fileset::FileEntry;
 use crate::parse::cob::Cob;
-use crate::report::{untidy, warn, ErrorKey};
+use crate::report::{note_comment, untidy, warn, ErrorKey};
 use crate::token::{leak, Loc, Token};
 
 fn is_key_char(c: char) -> bool {
@@ -48,7 +48,8 @@ impl LocaParser {
                 chars.next();
             }
         } else {
-            warn(ErrorKey::Encoding).msg("Expected UTF-8 BOM encoding").loc(loc).push();
+            let msg = "Expected UTF-8 BOM encoding";
+            warn(ErrorKey::Encoding).msg(msg).loc(loc).fix_encoding().push();
         }
         LocaParser {
             loc,
@@ -105,6 +106,13 @@ impl LocaParser {
         self.next_char(); // Eat the newline
     }
 
+    /// Look at the comment that starts at the current char, which must be a `#`.
+    /// This does not consume it.
+    fn note_comment(&self, trailing: bool) {
+        let comment = self.content[self.offset + 1..].split('\n').next().unwrap_or_default();
+        note_comment(comment, self.loc, trailing);
+    }
+
     // This function returns an Option so that the caller can return
     // its value without further boilerplate.
     #[allow(clippy::unnecessary_wraps)]
@@ -238,6 +246,7 @@ impl LocaParser {
             // Skip comments and blank lines
             self.skip_whitespace();
             if self.chars.peek() == Some(&'#') {
+                self.note_comment(false);
                 self.skip_line();
                 continue;
             }
@@ -269,9 +278,11 @@ impl LocaParser {
             return self.error_line(key);
         }
 
-        // Skip optional number after :
-        while let Some(c) = self.chars.peek() {
-            if c.is_ascii_digit() {
+        // Optional version number after :
+        let mut version = 0u32;
+        while let Some(&c) = self.chars.peek() {
+            if let Some(digit) = c.to_digit(10) {
+                version = version.saturating_mul(10).saturating_add(digit);
                 self.next_char();
             } else {
                 break;
@@ -340,7 +351,8 @@ impl LocaParser {
 
         self.skip_linear_whitespace();
         match self.chars.peek() {
-            None | Some('#' | '\n') => (),
+            Some('#') => self.note_comment(true),
+            None | Some('\n') => (),
             _ => {
                 let msg = "content after final `\"` on line";
                 warn(ErrorKey::Localization).strong().msg(msg).loc(self.loc).push();
@@ -353,7 +365,7 @@ impl LocaParser {
         } else {
             LocaValue::Concat(take(&mut self.value))
         };
-        Some(LocaEntry::new(key, value, Some(token)))
+        Some(LocaEntry::new(key, value, Some(token)).with_version(version))
     }
 }
 
@@ -683,7 +695,7 @@ impl<'a> ValueParser<'a> {
             // Clean up leftover state at end
             match state {
                 State::InKey(_) => {
-                    self.value.push(LocaValue::Markup);
+                    self.value.push(LocaValue::Markup(Token::new(&text, loc)));
                 }
                 State::InValue(key, value, loc, bracecount) => {
                     if key.to_ascii_lowercase() == "tooltip" {
@@ -694,7 +706,7 @@ impl<'a> ValueParser<'a> {
                         warn(ErrorKey::Markup).msg(msg).loc(self.loc).push();
                         self.value.push(LocaValue::Error);
                     } else {
-                        self.value.push(LocaValue::Markup);
+                        self.value.push(LocaValue::Markup(Token::new(&text, loc)));
                     }
                 }
             }
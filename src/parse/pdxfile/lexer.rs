@@ -6,7 +6,7 @@ use crate::block::Comparator;
 use crate::block::Eq::Single;
 use crate::game::Game;
 use crate::parse::pdxfile::{CharExt, Cob};
-use crate::report::{err, untidy, warn, ErrorKey};
+use crate::report::{err, note_comment, untidy, warn, ErrorKey};
 use crate::token::{Loc, Token};
 
 /// ^Z is by convention an end-of-text marker, and the game engine treats it as such.
@@ -318,6 +318,11 @@ impl Iterator for Lexer<'_> {
                 }
                 '#' => {
                     // A comment
+                    let input = self.inputs[self.inputs_index].as_str();
+                    let comment = input[i + 1..].split('\n').next().unwrap_or_default();
+                    let before = input[..i].rsplit('\n').next().unwrap_or_default();
+                    let trailing = !before.trim().is_empty();
+                    note_comment(comment, self.loc, trailing);
                     self.consume();
                     while let Some((_, c)) = self.peek() {
                         self.consume();
@@ -474,6 +479,7 @@ impl Iterator for Lexer<'_> {
                             .msg(msg)
                             .info(info)
                             .loc(self.loc)
+                            .suggest(self.loc, "\t".repeat(self.brace_depth))
                             .push();
                     }
                     let token = Token::from_static_str("}", self.loc);
@@ -2,15 +2,15 @@
 //!
 //! The main entry point is [`PdxFile`].
 
-#[cfg(feature = "ck3")]
-use std::fs::read;
-use std::fs::read_to_string;
+use std::io::ErrorKind;
 
 #[cfg(feature = "ck3")]
 use encoding_rs::{UTF_8, WINDOWS_1252};
 
 use crate::block::Block;
-use crate::fileset::FileEntry;
+#[cfg(feature = "ck3")]
+use crate::fileset::read_file_bytes;
+use crate::fileset::{read_file_to_string, FileEntry};
 use crate::parse::pdxfile::parse_pdx_file;
 #[cfg(feature = "ck3")]
 use crate::parse::pdxfile::{parse_reader_export, PdxfileMemory};
@@ -34,12 +34,18 @@ pub struct PdxFile {}
 impl PdxFile {
     /// Internal function to read a file in UTF-8 encoding.
     fn read_utf8(entry: &FileEntry) -> Option<String> {
-        match read_to_string(entry.fullpath()) {
+        match read_file_to_string(entry.fullpath()) {
             Ok(contents) => Some(contents),
             Err(e) => {
                 let msg = "could not read file";
                 let info = &format!("{e:#}");
-                err(ErrorKey::ReadError).msg(msg).info(info).loc(entry).push();
+                let builder = err(ErrorKey::ReadError).msg(msg).info(info).loc(entry);
+                if e.kind() == ErrorKind::InvalidData {
+                    // Not UTF-8, so most likely saved in a Windows encoding.
+                    builder.fix_encoding().push();
+                } else {
+                    builder.push();
+                }
                 None
             }
         }
@@ -52,7 +58,7 @@ impl PdxFile {
             Some(parse_pdx_file(entry, contents, BOM_UTF8_LEN, parser))
         } else {
             let msg = "file must start with a UTF-8 BOM";
-            warn(ErrorKey::Encoding).msg(msg).loc(entry).push();
+            warn(ErrorKey::Encoding).msg(msg).loc(entry).fix_encoding().push();
             Some(parse_pdx_file(entry, contents, 0, parser))
         }
     }
@@ -70,7 +76,7 @@ impl PdxFile {
     /// Parse a file that may be in UTF-8 with BOM encoding, or Windows-1252 encoding.
     #[cfg(feature = "ck3")]
     pub fn read_detect_encoding(entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        let bytes = match read(entry.fullpath()) {
+        let bytes = match read_file_bytes(entry.fullpath()) {
             Ok(bytes) => bytes,
             Err(e) => {
                 let msg = "could not read file";
@@ -120,7 +126,7 @@ impl PdxFile {
                 parse_reader_export(entry, contents, BOM_UTF8_LEN, memory);
             } else {
                 let msg = "file must start with a UTF-8 BOM";
-                warn(ErrorKey::Encoding).msg(msg).loc(entry).push();
+                warn(ErrorKey::Encoding).msg(msg).loc(entry).fix_encoding().push();
                 parse_reader_export(entry, contents, 0, memory);
             }
         }
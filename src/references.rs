@@ -0,0 +1,104 @@
+//! An index of where items are defined and where they are referred to, so that external tools
+//! can offer go-to-definition and find-all-references for script files.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use strum::IntoEnumIterator;
+
+use crate::everything::Everything;
+use crate::fileset::FileKind;
+use crate::helpers::TigerHashMap;
+use crate::item::Item;
+use crate::token::Loc;
+
+/// The references seen during validation.
+#[derive(Debug, Default)]
+pub(crate) struct References {
+    refs: Mutex<Vec<(Item, String, Loc)>>,
+}
+
+#[derive(Default)]
+struct Entry {
+    definitions: Vec<Loc>,
+    references: Vec<Loc>,
+}
+
+impl References {
+    pub(crate) fn add(&self, itype: Item, key: &str, loc: Loc) {
+        self.refs.lock().unwrap().push((itype, key.to_owned(), loc));
+    }
+
+    /// Write the index as a JSON array with one entry per item.
+    ///
+    /// Only items that are defined or referred to in the mods are included. The full index for
+    /// vanilla would be very large and is rarely needed.
+    pub(crate) fn write_json(&self, data: &Everything, fullpath: &Path) -> Result<()> {
+        let refs = self.refs.lock().unwrap();
+        let mut entries: TigerHashMap<(Item, Cow<str>), Entry> = TigerHashMap::default();
+        for itype in Item::iter() {
+            for token in data.iter_keys(itype) {
+                // Files are referred to by their path, but their keys are just the filename.
+                let key = if itype == Item::File {
+                    token.loc.pathname().to_string_lossy()
+                } else {
+                    Cow::Borrowed(token.as_str())
+                };
+                entries.entry((itype, key)).or_default().definitions.push(token.loc);
+            }
+        }
+        for (itype, key, loc) in refs.iter() {
+            let key = Cow::Borrowed(key.as_str());
+            entries.entry((*itype, key)).or_default().references.push(*loc);
+        }
+
+        let mut entries: Vec<_> = entries
+            .into_iter()
+            .filter(|(_, entry)| {
+                entry.definitions.iter().chain(&entry.references).any(|loc| is_mod(loc.kind))
+            })
+            .collect();
+        entries.sort_unstable_by(|((itype_a, key_a), _), ((itype_b, key_b), _)| {
+            (<&str>::from(*itype_a), key_a).cmp(&(<&str>::from(*itype_b), key_b))
+        });
+        let json: Vec<Value> = entries
+            .into_iter()
+            .map(|((itype, key), mut entry)| {
+                for locs in [&mut entry.definitions, &mut entry.references] {
+                    locs.sort_unstable();
+                    // Locations in macro expansions differ only in their link to the macro call.
+                    locs.dedup_by_key(|loc| (loc.idx, loc.line, loc.column));
+                }
+                json!({
+                    "item": <&str>::from(itype),
+                    "key": key,
+                    "definitions": entry.definitions.into_iter().map(loc_json).collect::<Vec<_>>(),
+                    "references": entry.references.into_iter().map(loc_json).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+
+        let writer = BufWriter::new(File::create(fullpath)?);
+        serde_json::to_writer_pretty(writer, &json)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn is_mod(kind: FileKind) -> bool {
+    matches!(kind, FileKind::LoadedMod(_) | FileKind::Mod)
+}
+
+/// Describe a location in the same way as the JSON output of the reports does.
+pub(crate) fn loc_json(loc: Loc) -> Value {
+    json!({
+        "path": loc.pathname(),
+        "fullpath": loc.fullpath(),
+        "linenr": if loc.line == 0 { None } else { Some(loc.line) },
+        "column": if loc.column == 0 { None } else { Some(loc.column) },
+    })
+}
@@ -0,0 +1,198 @@
+//! Baselines record the reports of an earlier run, so that later runs only show what's new.
+//!
+//! Unlike the suppression files from `--json` output, reports are matched by a fuzzy fingerprint
+//! that survives small edits to the files and to the report messages.
+
+use std::fs::{read_to_string, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::TigerHashMap;
+use crate::report::errors::{Cache, Errors};
+use crate::report::{ErrorKey, LogReport};
+
+/// The parts of a report that identify it across runs. Line and column numbers are left out,
+/// since they shift with unrelated edits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Fingerprint {
+    key: ErrorKey,
+    /// The message, lowercased and with numbers and whitespace normalized.
+    message: String,
+    /// The key of the top-level item that the report is about, if it could be determined.
+    item: Option<String>,
+    /// The file that the report is about, relative to its mod or game directory.
+    path: String,
+}
+
+impl Fingerprint {
+    fn new(cache: &mut Cache, report: &LogReport) -> Self {
+        let loc = report.primary().loc;
+        Fingerprint {
+            key: report.key,
+            message: normalize(&report.msg),
+            item: cache.get_item_key(loc).map(str::to_owned),
+            path: loc.pathname().to_string_lossy().replace('\\', "/"),
+        }
+    }
+}
+
+/// Lowercase the message, replace every number in it with `#`, and collapse whitespace.
+/// Numbers are often counts or line numbers that shift with unrelated edits.
+fn normalize(msg: &str) -> String {
+    let mut normalized = String::with_capacity(msg.len());
+    let mut prev = ' ';
+    for c in msg.trim().chars() {
+        let c = if c.is_ascii_digit() {
+            '#'
+        } else if c.is_whitespace() {
+            ' '
+        } else {
+            c.to_ascii_lowercase()
+        };
+        if !(c == prev && (c == '#' || c == ' ')) {
+            normalized.push(c);
+        }
+        prev = c;
+    }
+    normalized
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Baseline {
+    /// How often each fingerprint occurs in the baseline.
+    entries: TigerHashMap<Fingerprint, usize>,
+    /// How many baseline entries did not match any report the last time it was applied.
+    stale: usize,
+}
+
+impl Baseline {
+    /// Remove the reports that match entries in the baseline. Each entry matches only one
+    /// report, so that new occurrences of the same problem in the same item are still shown.
+    pub(crate) fn apply(&mut self, cache: &mut Cache, reports: &mut Vec<LogReport>) {
+        let mut remaining = self.entries.clone();
+        reports.retain(|report| {
+            if let Some(count) = remaining.get_mut(&Fingerprint::new(cache, report)) {
+                if *count > 0 {
+                    *count -= 1;
+                    return false;
+                }
+            }
+            true
+        });
+        self.stale = remaining.values().sum();
+    }
+}
+
+/// Load a baseline file written by [`write_baseline_json`]. From then on, reports that match it
+/// are left out of the output.
+pub fn baseline_from_json(fullpath: &Path) -> Result<()> {
+    let fingerprints: Vec<Fingerprint> = serde_json::from_str(&read_to_string(fullpath)?)?;
+    let mut baseline = Baseline::default();
+    for fingerprint in fingerprints {
+        *baseline.entries.entry(fingerprint).or_default() += 1;
+    }
+    Errors::get_mut().baseline = Some(baseline);
+    Ok(())
+}
+
+/// Take all the stored reports and write their fingerprints to a baseline file, for use with
+/// [`baseline_from_json`] in later runs.
+///
+/// Returns the number of entries written.
+pub fn write_baseline_json(fullpath: &Path) -> Result<usize> {
+    let mut errors = Errors::get_mut();
+    let reports = errors.take_reports();
+    let mut fingerprints: Vec<Fingerprint> =
+        reports.iter().map(|report| Fingerprint::new(&mut errors.cache, report)).collect();
+    // Sort them so that the file can be usefully kept under version control.
+    fingerprints.sort_by(|a, b| {
+        (&a.path, &a.item, a.key.to_string(), &a.message).cmp(&(
+            &b.path,
+            &b.item,
+            b.key.to_string(),
+            &b.message,
+        ))
+    });
+    let writer = BufWriter::new(File::create(fullpath)?);
+    serde_json::to_writer_pretty(writer, &fingerprints)?;
+    Ok(fingerprints.len())
+}
+
+/// Return the fingerprint by which a baseline would recognize `report`.
+///
+/// This looks at the report's file as it is now, so it should be called while the report is
+/// still current.
+pub fn report_fingerprint(report: &LogReport) -> Fingerprint {
+    Fingerprint::new(&mut Errors::get_mut().cache, report)
+}
+
+/// Return how many entries of the loaded baseline did not match any report when the reports
+/// were last emitted, or `None` if no baseline was loaded.
+pub fn baseline_stale_entries() -> Option<usize> {
+    Errors::get().baseline.as_ref().map(|baseline| baseline.stale)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::fileset::FileKind;
+    use crate::report::{Confidence, PointedMessage, Severity};
+    use crate::token::Loc;
+
+    fn report(pathname: &str, msg: &str) -> LogReport {
+        let loc = Loc::for_file(PathBuf::from(pathname), FileKind::Mod, PathBuf::from(pathname));
+        LogReport {
+            severity: Severity::Warning,
+            confidence: Confidence::Reasonable,
+            key: ErrorKey::Validation,
+            msg: msg.to_owned(),
+            info: None,
+            pointers: vec![PointedMessage::new(loc)],
+            fixes: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  found 3 items,\tExpected  12 "), "found # items, expected #");
+        assert_eq!(normalize("line 10 and line 200"), normalize("line 11 and line 2"));
+        // Names are kept apart, even though their case is not.
+        assert_eq!(normalize("unknown field `Foo_1`"), "unknown field `foo_#`");
+        assert_ne!(normalize("unknown field `foo`"), normalize("unknown field `bar`"));
+        assert_eq!(normalize("trait `Brave`"), normalize("trait `brave`"));
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut cache = Cache::default();
+        let mut baseline = Baseline::default();
+        for (pathname, msg) in [("a.txt", "problem 1"), ("a.txt", "problem 2"), ("b.txt", "gone")] {
+            let fingerprint = Fingerprint::new(&mut cache, &report(pathname, msg));
+            *baseline.entries.entry(fingerprint).or_default() += 1;
+        }
+
+        // Each baseline entry hides only one report, so the third occurrence is new.
+        let mut reports = vec![
+            report("a.txt", "problem 3"),
+            report("a.txt", "problem 4"),
+            report("a.txt", "problem 5"),
+            report("c.txt", "problem 1"),
+        ];
+        baseline.apply(&mut cache, &mut reports);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].msg, "problem 5");
+        assert_eq!(reports[1].primary().loc.pathname(), Path::new("c.txt"));
+        assert_eq!(baseline.stale, 1);
+
+        // The stale count is recomputed every time.
+        let mut reports = Vec::new();
+        baseline.apply(&mut cache, &mut reports);
+        assert_eq!(baseline.stale, 3);
+    }
+}
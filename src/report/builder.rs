@@ -4,7 +4,10 @@
 //! - The user is forced to add at least one pointer, making it impossible to create a report
 //!     without pointers, which would lead to panics.
 
-use crate::report::{log, Confidence, ErrorKey, ErrorLoc, LogReport, PointedMessage, Severity};
+use crate::report::{
+    log, Confidence, ErrorKey, ErrorLoc, Fix, FixKind, LogReport, PointedMessage, Severity,
+};
+use crate::token::Loc;
 
 // =================================================================================================
 // =============== Starting points:
@@ -101,6 +104,8 @@ impl ReportBuilderStage2 {
             msg: self.msg,
             info: self.info,
             pointers: vec![PointedMessage { loc: eloc.into_loc(), length, msg: None }],
+            fixes: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
@@ -112,11 +117,20 @@ impl ReportBuilderStage2 {
             msg: self.msg,
             info: self.info,
             pointers: vec![PointedMessage { loc: eloc.into_loc(), length, msg: Some(msg.into()) }],
+            fixes: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
     pub fn pointers(self, pointers: Vec<PointedMessage>) -> ReportBuilderStage3 {
-        ReportBuilderStage3 { stage1: self.stage1, msg: self.msg, info: self.info, pointers }
+        ReportBuilderStage3 {
+            stage1: self.stage1,
+            msg: self.msg,
+            info: self.info,
+            pointers,
+            fixes: Vec::new(),
+            suggestions: Vec::new(),
+        }
     }
 }
 
@@ -127,9 +141,47 @@ pub struct ReportBuilderStage3 {
     msg: String,
     info: Option<String>,
     pointers: Vec<PointedMessage>,
+    fixes: Vec<Fix>,
+    suggestions: Vec<Fix>,
 }
 
 impl ReportBuilderStage3 {
+    /// Offer to fix the problem by replacing the located text with `replacement`.
+    pub fn fix<E: ErrorLoc, S: Into<String>>(mut self, eloc: E, replacement: S) -> Self {
+        let length = eloc.loc_length();
+        self.fixes.push(Fix {
+            loc: eloc.into_loc(),
+            length,
+            replacement: replacement.into(),
+            kind: FixKind::Replace,
+        });
+        self
+    }
+    /// Suggest replacing the located text with `replacement`. Unlike [`Self::fix`], this is not
+    /// applied automatically, because it might be wrong.
+    pub fn suggest<E: ErrorLoc, S: Into<String>>(mut self, eloc: E, replacement: S) -> Self {
+        let length = eloc.loc_length();
+        self.suggestions.push(Fix {
+            loc: eloc.into_loc(),
+            length,
+            replacement: replacement.into(),
+            kind: FixKind::Replace,
+        });
+        self
+    }
+    /// Offer to fix the problem by saving the file of the primary location as UTF-8 with a BOM.
+    pub fn fix_encoding(mut self) -> Self {
+        if let Some(pointer) = self.pointers.first() {
+            let loc = Loc { line: 0, column: 0, link_idx: None, ..pointer.loc };
+            self.fixes.push(Fix {
+                loc,
+                length: 0,
+                replacement: String::new(),
+                kind: FixKind::Encoding,
+            });
+        }
+        self
+    }
     pub fn loc_msg<E: ErrorLoc, S: Into<String>>(mut self, eloc: E, msg: S) -> Self {
         let length = eloc.loc_length();
         self.pointers.push(PointedMessage { loc: eloc.into_loc(), length, msg: Some(msg.into()) });
@@ -156,6 +208,8 @@ impl ReportBuilderStage3 {
             msg: self.msg,
             info: self.info,
             pointers: self.pointers,
+            fixes: self.fixes,
+            suggestions: self.suggestions,
         }
     }
     /// Build the report and push it to be printed.
@@ -55,6 +55,9 @@ pub enum ErrorKey {
     Colors,
     UnusedLocalization,
     UnusedFile,
+    UnusedItem,
+    UnusedIgnore,
+    StaleLocalization,
     UnknownList,
     Choice,
     UseOfThis,
@@ -2,7 +2,7 @@
 
 use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::fs::{read, File};
+use std::fs::File;
 use std::io::{stdout, Write};
 use std::mem::take;
 use std::path::{Path, PathBuf};
@@ -12,13 +12,18 @@ use anyhow::Result;
 use encoding_rs::{UTF_8, WINDOWS_1252};
 use once_cell::sync::Lazy;
 
+use crate::fileset::read_file_bytes;
 use crate::helpers::{TigerHashMap, TigerHashSet};
 use crate::macros::MACRO_MAP;
+use crate::report::baseline::Baseline;
 use crate::report::error_loc::ErrorLoc;
 use crate::report::filter::ReportFilter;
+use crate::report::ignore::Ignores;
+use crate::report::overrides::SeverityOverrides;
 use crate::report::suppress::{Suppression, SuppressionKey};
 use crate::report::writer::log_report;
 use crate::report::writer_json::log_report_json;
+use crate::report::writer_sarif::log_reports_sarif;
 use crate::report::{ErrorKey, FilterRule, LogReport, OutputStyle, PointedMessage};
 use crate::token::{leak, Loc};
 
@@ -36,13 +41,23 @@ pub struct Errors {
 
     pub(crate) cache: Cache,
 
+    /// Changes the severity and confidence of reports before they are filtered.
+    pub(crate) overrides: SeverityOverrides,
     /// Determines whether a report should be printed.
     pub(crate) filter: ReportFilter,
     /// Output color and style configuration.
     pub(crate) styles: OutputStyle,
+    /// Whether to emit the reports as a SARIF document instead of the usual formats.
+    pub(crate) sarif: bool,
 
     pub(crate) suppress: TigerHashMap<SuppressionKey, Vec<Suppression>>,
 
+    /// The `# tiger-ignore` comments seen in the files.
+    pub(crate) ignores: Ignores,
+
+    /// Reports from an earlier run that should not be shown again.
+    pub(crate) baseline: Option<Baseline>,
+
     /// All reports that passed the checks, stored here to be sorted before being emitted all at once.
     /// The "abbreviated" reports don't participate in this. They are still emitted immediately.
     /// It's a `HashSet` because duplicate reports are fairly common due to macro expansion and other revalidations.
@@ -56,10 +71,14 @@ impl Default for Errors {
             loaded_mods_labels: Vec::default(),
             loaded_dlcs_labels: Vec::default(),
             cache: Cache::default(),
+            overrides: SeverityOverrides::default(),
             filter: ReportFilter::default(),
             styles: OutputStyle::default(),
+            sarif: false,
             storage: TigerHashSet::default(),
             suppress: TigerHashMap::default(),
+            ignores: Ignores::default(),
+            baseline: None,
         }
     }
 }
@@ -88,8 +107,14 @@ impl Errors {
 
     /// Perform some checks to see whether the report should actually be logged.
     /// If yes, it will add it to the storage.
-    fn push_report(&mut self, report: LogReport) {
-        if !self.filter.should_print_report(&report) || self.should_suppress(&report) {
+    fn push_report(&mut self, mut report: LogReport) {
+        self.overrides.apply(&mut report);
+        // Check the ignore comments first, so that they count as used even if the report would
+        // have been filtered out anyway.
+        if self.ignores.matches(&report)
+            || !self.filter.should_print_report(&report)
+            || self.should_suppress(&report)
+        {
             return;
         }
         self.storage.insert(report);
@@ -119,9 +144,13 @@ impl Errors {
     }
 
     /// Extract the stored reports, sort them, and return them as a vector of [`LogReport`].
-    /// The stored reports will be left empty.
+    /// The stored reports will be left empty. Reports that match the baseline, if one was
+    /// loaded, are left out.
     pub fn take_reports(&mut self) -> Vec<LogReport> {
         let mut reports: Vec<LogReport> = take(&mut self.storage).into_iter().collect();
+        if let Some(baseline) = &mut self.baseline {
+            baseline.apply(&mut self.cache, &mut reports);
+        }
         reports.sort_unstable_by(|a, b| {
             // Severity in descending order
             let mut cmp = b.severity.cmp(&a.severity);
@@ -156,16 +185,25 @@ impl Errors {
 
     /// Print all the stored reports to the error output.
     /// Set `json` if they should be printed as a JSON array. Otherwise they are printed in the
-    /// default output format.
+    /// default output format. If SARIF output was selected with [`set_output_sarif`], that
+    /// overrides both.
     ///
     /// Note that the default output format is not stable across versions. It is meant for human
     /// readability and occasionally gets changed to improve that.
     pub fn emit_reports(&mut self, json: bool) {
         let reports = self.take_reports();
-        if json {
+        self.emit_report_list(&reports, json);
+    }
+
+    /// Print the given reports to the error output, in the same way as
+    /// [`Errors::emit_reports`]. The stored reports are not affected.
+    pub fn emit_report_list(&mut self, reports: &[LogReport], json: bool) {
+        if self.sarif {
+            log_reports_sarif(self, reports);
+        } else if json {
             _ = writeln!(self.output.get_mut(), "[");
             let mut first = true;
-            for report in &reports {
+            for report in reports {
                 if !first {
                     _ = writeln!(self.output.get_mut(), ",");
                 }
@@ -174,13 +212,15 @@ impl Errors {
             }
             _ = writeln!(self.output.get_mut(), "\n]");
         } else {
-            for report in &reports {
+            for report in reports {
                 log_report(self, report);
             }
         }
     }
 
     pub fn store_source_file(&mut self, fullpath: PathBuf, source: &'static str) {
+        // The file may have been stored before, if it's being reloaded.
+        self.cache.linecache.remove(&fullpath);
         self.cache.filecache.insert(fullpath, source);
     }
 
@@ -230,7 +270,7 @@ impl Cache {
             self.linecache.insert(fullpath.to_path_buf(), lines);
             return line;
         }
-        let bytes = read(fullpath).ok()?;
+        let bytes = read_file_bytes(fullpath).ok()?;
         // Try decoding it as UTF-8. If that succeeds without errors, use it, otherwise fall back
         // to WINDOWS_1252. The decode method will do BOM stripping.
         let contents = match UTF_8.decode(&bytes) {
@@ -245,6 +285,27 @@ impl Cache {
         self.linecache.insert(fullpath.to_path_buf(), lines);
         line
     }
+
+    /// Find the key of the top-level item that `loc` is in, by looking back for the closest line
+    /// that starts a definition without indentation. In localization files, it's the key of the
+    /// entry on the line itself.
+    pub(crate) fn get_item_key(&mut self, loc: Loc) -> Option<&'static str> {
+        let line = self.get_line(loc)?;
+        if loc.pathname().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("yml")) {
+            let key = line.trim_start().split(':').next()?;
+            return (!key.is_empty() && !key.starts_with('#')).then_some(key);
+        }
+        let lines = self.linecache.get(loc.fullpath())?;
+        lines[..loc.line as usize].iter().rev().find_map(|line| {
+            let line = line.strip_prefix('\u{feff}').unwrap_or(line);
+            if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '}') {
+                return None;
+            }
+            let (key, rest) = line.split_once('=')?;
+            let key = key.trim_end();
+            (!key.is_empty() && !rest.starts_with('=')).then_some(key)
+        })
+    }
 }
 
 /// Record a secondary mod to be loaded before the one being validated.
@@ -312,6 +373,12 @@ pub fn emit_reports(json: bool) {
     Errors::get_mut().emit_reports(json);
 }
 
+/// Print the given reports to the error output, in the same way as [`emit_reports`].
+/// The stored reports are not affected.
+pub fn emit_report_list(reports: &[LogReport], json: bool) {
+    Errors::get_mut().emit_report_list(reports, json);
+}
+
 /// Extract the stored reports, sort them, and return them as a vector of [`LogReport`].
 /// The stored reports will be left empty.
 pub fn take_reports() -> Vec<LogReport> {
@@ -322,6 +389,12 @@ pub fn store_source_file(fullpath: PathBuf, source: &'static str) {
     Errors::get_mut().store_source_file(fullpath, source);
 }
 
+/// Return the contents of a script file as they were last parsed, if it was stored with
+/// [`store_source_file`].
+pub(crate) fn stored_source_file(fullpath: &Path) -> Option<&'static str> {
+    Errors::get().cache.filecache.get(fullpath).copied()
+}
+
 // =================================================================================================
 // =============== Deprecated legacy calls to submit reports:
 // =================================================================================================
@@ -354,6 +427,12 @@ pub fn disable_ansi_colors() {
     Errors::get_mut().styles = OutputStyle::no_color();
 }
 
+/// Select SARIF 2.1 as the output format for [`emit_reports`], for use with code scanning tools.
+/// It overrides the `json` argument.
+pub fn set_output_sarif(sarif: bool) {
+    Errors::get_mut().sarif = sarif;
+}
+
 // =================================================================================================
 // =============== Configuration (Filter):
 // =================================================================================================
@@ -374,3 +453,9 @@ pub fn set_show_loaded_mods(v: bool) {
 pub(crate) fn set_predicate(predicate: FilterRule) {
     Errors::get_mut().filter.predicate = predicate;
 }
+
+/// Configure the error reporter to change the severity and confidence of reports before
+/// filtering them.
+pub(crate) fn set_severity_overrides(overrides: SeverityOverrides) {
+    Errors::get_mut().overrides = overrides;
+}
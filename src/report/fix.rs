@@ -0,0 +1,287 @@
+//! Apply the machine-applicable fixes that reports can carry, for the `--fix` option.
+//!
+//! Fixes are only ever applied to the mod's own files, never to vanilla or to other loaded mods.
+
+use std::fs::{read, write};
+use std::io::{stdout, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+use crate::fileset::FileKind;
+use crate::helpers::{TigerHashMap, TigerHashSet};
+use crate::report::{Fix, FixKind, LogReport};
+
+const BOM: char = '\u{feff}';
+const BOM_BYTES: &[u8] = b"\xef\xbb\xbf";
+
+/// The number of unchanged lines shown around each change in the dry-run diff.
+const CONTEXT: usize = 3;
+
+/// What [`apply_fixes`] did.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FixSummary {
+    /// The number of fixes applied, or that would be applied in a dry run.
+    pub applied: usize,
+    /// The number of files changed, or that would be changed in a dry run.
+    pub files: usize,
+    /// The number of fixes that were not applied because they overlapped with another fix or
+    /// didn't fit the file.
+    pub skipped: usize,
+}
+
+/// Apply the fixes of these reports to the mod's files.
+///
+/// With `dry_run`, the files are left alone and the changes are printed to stdout as a unified
+/// diff instead. Otherwise, the reports whose fixes were all applied are removed from `reports`.
+pub fn apply_fixes(reports: &mut Vec<LogReport>, dry_run: bool) -> Result<FixSummary> {
+    let mut files: TigerHashMap<&'static Path, TigerHashSet<&Fix>> = TigerHashMap::default();
+    for fix in reports.iter().flat_map(|report| &report.fixes) {
+        if fix.loc.kind == FileKind::Mod && fix.loc.link_idx.is_none() {
+            files.entry(fix.loc.fullpath()).or_default().insert(fix);
+        }
+    }
+    let mut files: Vec<_> = files.into_iter().collect();
+    files.sort_unstable_by_key(|(fullpath, _)| *fullpath);
+
+    let mut summary = FixSummary::default();
+    let mut applied: TigerHashSet<Fix> = TigerHashSet::default();
+    for (fullpath, fixes) in files {
+        let mut fixes: Vec<&Fix> = fixes.into_iter().collect();
+        fixes.sort_unstable();
+        let bytes = read(fullpath)?;
+        let (old_text, encoding) = decode(&bytes);
+        let mut file = FixedFile::new(&old_text);
+        for fix in fixes {
+            if file.apply(fix) {
+                applied.insert(fix.clone());
+            } else {
+                summary.skipped += 1;
+            }
+        }
+        if file.applied == 0 {
+            continue;
+        }
+        summary.applied += file.applied;
+        summary.files += 1;
+        // The file is always saved as UTF-8. It keeps its BOM, or gets one if a fix asked for it.
+        let had_bom = bytes.starts_with(BOM_BYTES);
+        let bom = had_bom || file.reencode;
+        if dry_run {
+            // Unified diffs can't show encoding changes, so describe them in the leading text,
+            // which patch tools ignore.
+            let pathname = fix_pathname(fullpath, reports);
+            let mut out = stdout().lock();
+            if encoding != UTF_8 {
+                let name = encoding.name();
+                writeln!(out, "Re-encode {} from {name} to UTF-8", pathname.display())?;
+            }
+            if bom && !had_bom {
+                writeln!(out, "Add a UTF-8 BOM to {}", pathname.display())?;
+            }
+            write_diff(&mut out, pathname, &file.old, &file.new)?;
+        } else {
+            let mut new_text = if bom { String::from(BOM) } else { String::new() };
+            new_text.extend(file.new.iter().map(String::as_str));
+            write(fullpath, new_text)?;
+        }
+    }
+    if !dry_run {
+        reports.retain(|report| {
+            report.fixes.is_empty() || !report.fixes.iter().all(|fix| applied.contains(fix))
+        });
+    }
+    Ok(summary)
+}
+
+/// Decode the file contents without their BOM. Files that aren't valid UTF-8, and don't have a
+/// UTF-16 BOM, are taken to be in the Windows-1252 encoding, which is what text editors on
+/// Windows tend to save them in.
+fn decode(bytes: &[u8]) -> (String, &'static Encoding) {
+    let mut bytes = bytes;
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        if encoding != UTF_8 {
+            let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+            return (text.into_owned(), encoding);
+        }
+        // A UTF-8 BOM doesn't guarantee that the rest of the file is UTF-8.
+        bytes = &bytes[bom_len..];
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_owned(), UTF_8),
+        Err(_) => (WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned(), WINDOWS_1252),
+    }
+}
+
+/// Find the pathname relative to the mod directory of the file at `fullpath`.
+fn fix_pathname(fullpath: &'static Path, reports: &[LogReport]) -> &'static Path {
+    reports
+        .iter()
+        .flat_map(|report| &report.fixes)
+        .find(|fix| fix.loc.fullpath() == fullpath)
+        .map_or(fullpath, |fix| fix.loc.pathname())
+}
+
+/// The lines of a file, before and after applying fixes. The lines include their line endings.
+struct FixedFile {
+    old: Vec<String>,
+    new: Vec<String>,
+    /// Per line, the character ranges that fixes have already replaced, in terms of the old line.
+    edited: Vec<Vec<(usize, usize)>>,
+    /// The length change so far per line, for fixes to the right of earlier ones.
+    /// Each entry is the old column and the number of chars added (or removed) there.
+    shifts: Vec<Vec<(usize, isize)>>,
+    /// Whether a fix asked for the file to be saved as UTF-8 with a BOM.
+    reencode: bool,
+    applied: usize,
+}
+
+impl FixedFile {
+    fn new(text: &str) -> Self {
+        let old: Vec<String> = text.split_inclusive('\n').map(str::to_owned).collect();
+        let len = old.len();
+        FixedFile {
+            new: old.clone(),
+            old,
+            edited: vec![Vec::new(); len],
+            shifts: vec![Vec::new(); len],
+            reencode: false,
+            applied: 0,
+        }
+    }
+
+    /// Apply the fix, and return whether it could be applied.
+    fn apply(&mut self, fix: &Fix) -> bool {
+        if fix.kind == FixKind::Encoding {
+            if !self.reencode {
+                self.reencode = true;
+                self.applied += 1;
+            }
+            return true;
+        }
+        let idx = fix.loc.line as usize - 1;
+        let Some(old_line) = self.old.get(idx) else {
+            return false;
+        };
+        let start = fix.loc.column.saturating_sub(1) as usize;
+        let end = start + fix.length;
+        if end > old_line.trim_end_matches(['\r', '\n']).chars().count()
+            || self.edited[idx].iter().any(|&(s, e)| (start < e && s < end) || start == s)
+        {
+            return false;
+        }
+        let shift: isize =
+            self.shifts[idx].iter().filter(|&&(col, _)| col <= start).map(|&(_, n)| n).sum();
+        let new_line = &mut self.new[idx];
+        let new_start = start.saturating_add_signed(shift);
+        let byte_start = char_to_byte(new_line, new_start);
+        let byte_end = char_to_byte(new_line, new_start + fix.length);
+        new_line.replace_range(byte_start..byte_end, &fix.replacement);
+        self.edited[idx].push((start, end));
+        #[allow(clippy::cast_possible_wrap)] // lines are nowhere near that long
+        let added = fix.replacement.chars().count() as isize - fix.length as isize;
+        self.shifts[idx].push((start, added));
+        self.applied += 1;
+        true
+    }
+}
+
+fn char_to_byte(s: &str, chars: usize) -> usize {
+    s.char_indices().nth(chars).map_or(s.len(), |(i, _)| i)
+}
+
+/// Write the changes as a unified diff, with [`CONTEXT`] lines of context around each change.
+/// The new version of each line may contain several lines, if a fix inserted line breaks.
+fn write_diff(out: &mut impl Write, pathname: &Path, old: &[String], new: &[String]) -> Result<()> {
+    let changed: Vec<usize> = (0..old.len()).filter(|&i| old[i] != new[i]).collect();
+    if changed.is_empty() {
+        return Ok(());
+    }
+    let pathname = pathname.to_string_lossy().replace('\\', "/");
+    writeln!(out, "--- a/{pathname}")?;
+    writeln!(out, "+++ b/{pathname}")?;
+
+    let new_len = |i: usize| new[i].split_inclusive('\n').count();
+    // The difference in line numbers between the old and new file so far.
+    let mut offset = 0;
+    let mut next = 0;
+    while next < changed.len() {
+        // Gather the changes that are close enough together to go into one hunk.
+        let first = changed[next];
+        let mut last = first;
+        next += 1;
+        while next < changed.len() && changed[next] <= last + 2 * CONTEXT + 1 {
+            last = changed[next];
+            next += 1;
+        }
+        let start = first.saturating_sub(CONTEXT);
+        let end = (last + CONTEXT + 1).min(old.len());
+        let old_count = end - start;
+        let new_count: usize =
+            (start..end).map(|i| if old[i] == new[i] { 1 } else { new_len(i) }).sum();
+        writeln!(
+            out,
+            "@@ -{},{old_count} +{},{new_count} @@",
+            start + 1,
+            (start + 1).saturating_add_signed(offset)
+        )?;
+        for i in start..end {
+            if old[i] == new[i] {
+                write_line(out, ' ', &old[i])?;
+            } else {
+                write_line(out, '-', &old[i])?;
+                for line in new[i].split_inclusive('\n') {
+                    write_line(out, '+', line)?;
+                }
+            }
+        }
+        #[allow(clippy::cast_possible_wrap)] // files are nowhere near that long
+        {
+            offset += new_count as isize - old_count as isize;
+        }
+    }
+    Ok(())
+}
+
+fn write_line(out: &mut impl Write, prefix: char, line: &str) -> Result<()> {
+    write!(out, "{prefix}{line}")?;
+    if !line.ends_with('\n') {
+        writeln!(out, "\n\\ No newline at end of file")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::token::Loc;
+
+    #[test]
+    fn test_apply() {
+        let loc =
+            Loc::for_file(PathBuf::from("test.txt"), FileKind::Mod, PathBuf::from("test.txt"));
+        let fix = |line, column, length, replacement: &str| Fix {
+            loc: Loc { line, column, ..loc },
+            length,
+            replacement: replacement.to_owned(),
+            kind: FixKind::Replace,
+        };
+        let mut file = FixedFile::new("a = {\n\tb = c d = e\n}");
+        assert!(file.apply(&fix(2, 2, 1, "bb")));
+        assert!(file.apply(&fix(2, 8, 1, "dd")));
+        assert!(!file.apply(&fix(2, 8, 1, "ddd")));
+        assert!(!file.apply(&fix(3, 1, 2, "")));
+        assert!(file.apply(&fix(3, 1, 0, "\t")));
+        assert_eq!(file.new, vec!["a = {\n", "\tbb = c dd = e\n", "\t}"]);
+        assert_eq!(file.applied, 3);
+
+        let encoding = Fix { kind: FixKind::Encoding, ..fix(0, 0, 0, "") };
+        assert!(file.apply(&encoding));
+        assert!(file.apply(&encoding));
+        assert!(file.reencode);
+        assert_eq!(file.applied, 4);
+    }
+}
@@ -0,0 +1,267 @@
+//! Inline suppression of reports with `# tiger-ignore: <key>` comments in the mod's files.
+//!
+//! A comment on a line of its own applies to the line below it. A comment at the end of a line
+//! applies to that line. Several keys can be given, separated by commas.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::helpers::TigerHashMap;
+use crate::pathtable::{PathTable, PathTableIndex};
+use crate::report::errors::Errors;
+use crate::report::{warn, ErrorKey, LogReport};
+use crate::token::Loc;
+
+const MARKER: &str = "tiger-ignore";
+
+#[derive(Debug)]
+struct Ignore {
+    key: ErrorKey,
+    /// The location of the comment.
+    loc: Loc,
+    /// Whether any report from loading the files was ignored because of this comment.
+    used_loading: bool,
+    /// Whether any report from the latest validation was ignored because of this comment.
+    used: bool,
+    /// The `used` flag from the validation before that, for when only some files are validated
+    /// again.
+    used_before: bool,
+}
+
+impl Ignore {
+    fn is_used(&self) -> bool {
+        self.used_loading || self.used
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Ignores {
+    /// The ignore comments, by the file and line they apply to.
+    lines: TigerHashMap<(PathTableIndex, u32), Vec<Ignore>>,
+    /// Whether the reports come from validation rather than from loading the files.
+    validating: bool,
+}
+
+impl Ignores {
+    fn insert(&mut self, key: ErrorKey, loc: Loc, line: u32) {
+        let ignores = self.lines.entry((loc.idx, line)).or_default();
+        // Files containing macros are lexed again for every macro expansion.
+        if !ignores.iter().any(|ignore| ignore.key == key && ignore.loc == loc) {
+            ignores.push(Ignore { key, loc, used_loading: false, used: false, used_before: false });
+        }
+    }
+
+    /// Return true iff an ignore comment applies to the report, and remember that it was used.
+    pub(crate) fn matches(&mut self, report: &LogReport) -> bool {
+        if self.lines.is_empty() {
+            return false;
+        }
+        let loc = report.primary().loc;
+        if let Some(ignores) = self.lines.get_mut(&(loc.idx, loc.line)) {
+            for ignore in ignores {
+                if ignore.key == report.key {
+                    if self.validating {
+                        ignore.used = true;
+                    } else {
+                        ignore.used_loading = true;
+                    }
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Check if a comment is an ignore comment, and if so remember it.
+///
+/// `comment` is the text after the `#`, and `loc` is the location of the `#`. `trailing` should
+/// be true if the comment follows other content on the same line.
+pub(crate) fn note_comment(comment: &str, loc: Loc, trailing: bool) {
+    let Some(keys) = comment.trim_start().strip_prefix(MARKER) else {
+        return;
+    };
+    let Some(keys) = keys.trim_start().strip_prefix(':') else {
+        let msg = format!("expected `{MARKER}:` followed by the keys of the reports to ignore");
+        warn(ErrorKey::ParseError).msg(msg).loc(loc).push();
+        return;
+    };
+    let line = if trailing { loc.line } else { loc.line + 1 };
+    for key in keys.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+        if let Ok(key) = ErrorKey::from_str(key) {
+            Errors::get_mut().ignores.insert(key, loc, line);
+        } else {
+            let msg = format!("unknown report key `{key}` in ignore comment");
+            warn(ErrorKey::Validation).msg(msg).loc(loc).push();
+        }
+    }
+}
+
+/// Forget the ignore comments in a file, because it's about to be read again.
+pub(crate) fn forget_comments(file: Loc) {
+    Errors::get_mut().ignores.lines.retain(|(idx, _), _| *idx != file.idx);
+}
+
+/// Note that the files are about to be loaded, so that the ignore comments used by their reports
+/// stay used when the files are validated again.
+pub(crate) fn start_loading() {
+    Errors::get_mut().ignores.validating = false;
+}
+
+/// Note that the files are about to be validated, possibly not for the first time, and forget
+/// which ignore comments the previous validation used.
+pub(crate) fn start_validating() {
+    let ignores = &mut Errors::get_mut().ignores;
+    ignores.validating = true;
+    for ignore in ignores.lines.values_mut().flatten() {
+        ignore.used_before = ignore.used;
+        ignore.used = false;
+    }
+}
+
+/// After validating only the files in `validated`, which are full paths, let the ignore comments
+/// in the other files keep their uses from the previous validation.
+pub(crate) fn keep_uses_outside(validated: &[PathBuf]) {
+    for ((idx, _), ignores) in &mut Errors::get_mut().ignores.lines {
+        let fullpath = PathTable::lookup_fullpath(*idx);
+        if !validated.iter().any(|path| path == fullpath) {
+            for ignore in ignores {
+                ignore.used |= ignore.used_before;
+            }
+        }
+    }
+}
+
+/// Warn about ignore comments that did not match any report. This should be called after all
+/// the validations whose reports the comments may be about.
+pub fn warn_unused_ignores() {
+    let mut unused = Vec::new();
+    for ignores in Errors::get().ignores.lines.values() {
+        for ignore in ignores.iter().filter(|ignore| !ignore.is_used()) {
+            unused.push((ignore.key, ignore.loc));
+        }
+    }
+    for (key, loc) in unused {
+        let msg = format!("no `{key}` report to ignore here");
+        warn(ErrorKey::UnusedIgnore).msg(msg).loc(loc).push();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::fileset::FileKind;
+
+    /// The ignore comments are global state, so these tests must not run at the same time.
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    fn file_loc(pathname: &str) -> Loc {
+        Loc::for_file(PathBuf::from(pathname), FileKind::Mod, PathBuf::from(pathname))
+    }
+
+    fn line_loc(file: Loc, line: u32) -> Loc {
+        Loc { line, column: 1, ..file }
+    }
+
+    /// Return the keys of the ignore comments that apply to this line.
+    fn keys_at(loc: Loc, line: u32) -> Vec<ErrorKey> {
+        let errors = Errors::get();
+        errors
+            .ignores
+            .lines
+            .get(&(loc.idx, line))
+            .map_or_else(Vec::new, |ignores| ignores.iter().map(|ignore| ignore.key).collect())
+    }
+
+    /// Take the stored reports about the file of `loc`.
+    fn take_reports_at(loc: Loc) -> Vec<LogReport> {
+        let mut reports = Errors::get_mut().take_reports();
+        reports.retain(|report| report.primary().loc.idx == loc.idx);
+        reports
+    }
+
+    #[test]
+    fn test_note_comment() {
+        let _guard = LOCK.lock().unwrap();
+        let file = file_loc("ignore-test-1.txt");
+        let loc = line_loc(file, 5);
+
+        // A comment on a line of its own applies to the next line.
+        note_comment(" tiger-ignore: validation, missing-item", loc, false);
+        assert_eq!(keys_at(loc, 6), vec![ErrorKey::Validation, ErrorKey::MissingItem]);
+        assert!(keys_at(loc, 5).is_empty());
+
+        // A trailing comment applies to its own line.
+        let loc = line_loc(file, 8);
+        note_comment("tiger-ignore:missing-item   unknown-field", loc, true);
+        assert_eq!(keys_at(loc, 8), vec![ErrorKey::MissingItem, ErrorKey::UnknownField]);
+
+        // Lexing the same comment again doesn't duplicate it.
+        note_comment("tiger-ignore:missing-item   unknown-field", loc, true);
+        assert_eq!(keys_at(loc, 8).len(), 2);
+
+        forget_comments(loc);
+        assert!(keys_at(loc, 6).is_empty());
+        assert!(keys_at(loc, 8).is_empty());
+        assert!(take_reports_at(loc).is_empty());
+    }
+
+    #[test]
+    fn test_note_comment_malformed() {
+        let _guard = LOCK.lock().unwrap();
+        let loc = line_loc(file_loc("ignore-test-2.txt"), 3);
+
+        note_comment(" an ordinary comment", loc, false);
+        assert!(take_reports_at(loc).is_empty());
+
+        note_comment(" tiger-ignore missing-item", loc, false);
+        let reports = take_reports_at(loc);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].key, ErrorKey::ParseError);
+        assert!(keys_at(loc, 4).is_empty());
+
+        // The known keys still count.
+        note_comment(" tiger-ignore: no-such-key, missing-item", loc, false);
+        let reports = take_reports_at(loc);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].key, ErrorKey::Validation);
+        assert!(reports[0].msg.contains("no-such-key"));
+        assert_eq!(keys_at(loc, 4), vec![ErrorKey::MissingItem]);
+
+        forget_comments(loc);
+    }
+
+    #[test]
+    fn test_warn_unused_ignores() {
+        let _guard = LOCK.lock().unwrap();
+        let file = file_loc("ignore-test-3.txt");
+        let loc = line_loc(file, 1);
+        let used_loc = line_loc(file, 2);
+        let unused_loc = line_loc(file, 3);
+
+        start_loading();
+        note_comment("tiger-ignore: missing-item", loc, false);
+        note_comment("tiger-ignore: missing-item", unused_loc, true);
+        start_validating();
+        warn(ErrorKey::MissingItem).msg("ignored").loc(used_loc).push();
+        warn(ErrorKey::MissingItem).msg("not ignored").loc(loc).push();
+        warn_unused_ignores();
+
+        let reports = take_reports_at(loc);
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().any(|report| report.msg == "not ignored"));
+        let unused: Vec<_> =
+            reports.iter().filter(|report| report.key == ErrorKey::UnusedIgnore).collect();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].primary().loc.line, 3);
+
+        // A new validation forgets the earlier uses.
+        start_validating();
+        warn_unused_ignores();
+        assert_eq!(take_reports_at(loc).len(), 2);
+
+        forget_comments(loc);
+    }
+}
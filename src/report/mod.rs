@@ -1,21 +1,38 @@
 //! Error report collection and printing facilities.
 
+pub use baseline::{
+    baseline_from_json, baseline_stale_entries, report_fingerprint, write_baseline_json,
+    Fingerprint,
+};
 pub(crate) use builder::{err, fatal, report, tips, untidy, warn, ReportBuilderStage3};
 pub(crate) use error_key::ErrorKey;
 pub(crate) use error_loc::ErrorLoc;
 pub use errors::*;
 pub(crate) use filter::FilterRule;
+pub use fix::{apply_fixes, FixSummary};
+pub use ignore::warn_unused_ignores;
+pub(crate) use ignore::{
+    forget_comments as forget_ignore_comments, keep_uses_outside as keep_ignore_uses_outside,
+    note_comment, start_loading as start_ignore_loading,
+    start_validating as start_ignore_validating,
+};
 pub(crate) use output_style::OutputStyle;
-pub use report_struct::{Confidence, LogReport, PointedMessage, Severity};
+pub(crate) use overrides::{SeverityOverride, SeverityOverrides};
+pub use report_struct::{Confidence, Fix, FixKind, LogReport, PointedMessage, Severity};
 pub use suppress::suppress_from_json;
 
+mod baseline;
 mod builder;
 mod error_key;
 mod error_loc;
 mod errors;
 mod filter;
+mod fix;
+mod ignore;
 mod output_style;
+mod overrides;
 mod report_struct;
 mod suppress;
 mod writer;
 mod writer_json;
+mod writer_sarif;
@@ -0,0 +1,101 @@
+//! Changes to the severity and confidence of reports, configured in the `severity_overrides` block
+//! of the config file. They are applied before the reports are filtered.
+
+use crate::report::{Confidence, ErrorKey, LogReport, Severity};
+
+#[derive(Debug)]
+pub(crate) struct SeverityOverride {
+    pub(crate) key: ErrorKey,
+    /// Path patterns of the files this override is limited to. If empty, it applies everywhere.
+    pub(crate) files: Vec<String>,
+    pub(crate) severity: Option<Severity>,
+    pub(crate) confidence: Option<Confidence>,
+}
+
+impl SeverityOverride {
+    fn matches(&self, report: &LogReport) -> bool {
+        if report.key != self.key {
+            return false;
+        }
+        if self.files.is_empty() {
+            return true;
+        }
+        let path = report.primary().loc.pathname().to_string_lossy().replace('\\', "/");
+        self.files.iter().any(|pattern| path_matches(pattern, &path))
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SeverityOverrides {
+    pub(crate) overrides: Vec<SeverityOverride>,
+}
+
+impl SeverityOverrides {
+    /// Change the report's severity and confidence according to the overrides that match it.
+    /// When several match, the later ones win.
+    pub(crate) fn apply(&self, report: &mut LogReport) {
+        for o in &self.overrides {
+            if !o.matches(report) {
+                continue;
+            }
+            if let Some(severity) = o.severity {
+                report.severity = severity;
+            }
+            if let Some(confidence) = o.confidence {
+                report.confidence = confidence;
+            }
+        }
+    }
+}
+
+/// Match a path against a pattern with `*` (any characters except `/`), `**` (any characters),
+/// and `?` (one character). A pattern without wildcards matches the file itself and everything
+/// in the directory of that name, like the `file` rule in the filter does.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if !pattern.contains(['*', '?']) {
+        return path == pattern
+            || path.strip_prefix(pattern).is_some_and(|rest| rest.starts_with('/'));
+    }
+    glob_matches(pattern.as_bytes(), path.as_bytes())
+}
+
+fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            // Let `**/` also match zero directories.
+            let rest_after_slash = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=path.len()).any(|i| {
+                glob_matches(rest, &path[i..]) || glob_matches(rest_after_slash, &path[i..])
+            })
+        }
+        [b'*', rest @ ..] => {
+            let end = path.iter().position(|&c| c == b'/').unwrap_or(path.len());
+            (0..=end).any(|i| glob_matches(rest, &path[i..]))
+        }
+        [b'?', rest @ ..] => {
+            path.first().is_some_and(|&c| c != b'/') && glob_matches(rest, &path[1..])
+        }
+        [c, rest @ ..] => path.first() == Some(c) && glob_matches(rest, &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("common/traits", "common/traits/00_traits.txt"));
+        assert!(path_matches("common/traits/", "common/traits/00_traits.txt"));
+        assert!(!path_matches("common/trait", "common/traits/00_traits.txt"));
+        assert!(path_matches("events/*.txt", "events/my_events.txt"));
+        assert!(!path_matches("events/*.txt", "events/sub/my_events.txt"));
+        assert!(path_matches("events/**/*.txt", "events/my_events.txt"));
+        assert!(path_matches("events/**/*.txt", "events/sub/my_events.txt"));
+        assert!(path_matches("**/my_*.txt", "common/scripted_effects/my_effects.txt"));
+        assert!(path_matches("gfx/?.dds", "gfx/a.dds"));
+        assert!(!path_matches("gfx/?.dds", "gfx/ab.dds"));
+    }
+}
@@ -19,6 +19,11 @@ pub struct LogReport {
     pub info: Option<String>,
     /// Should contain one or more elements.
     pub pointers: Vec<PointedMessage>,
+    /// Edits that can be applied to the files to fix the problem. Usually empty.
+    pub fixes: Vec<Fix>,
+    /// Edits that might fix the problem, but need a human to choose between them, such as the
+    /// names of similar items. Usually empty.
+    pub suggestions: Vec<Fix>,
 }
 
 impl LogReport {
@@ -57,6 +62,30 @@ impl PointedMessage {
     }
 }
 
+/// A change to a file that fixes, or might fix, the problem a report is about.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fix {
+    /// Where the text to replace starts. For [`FixKind::Encoding`], this is the file as a whole.
+    pub loc: Loc,
+    /// The number of characters to replace. They must all be on the same line.
+    pub length: usize,
+    /// The new text.
+    pub replacement: String,
+    /// What kind of change this is.
+    pub kind: FixKind,
+}
+
+/// The kinds of changes that a [`Fix`] can make.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixKind {
+    /// Replace the located text with the replacement.
+    Replace,
+    /// Save the file as UTF-8 with a BOM. This fixes encoding problems. The `length` and
+    /// `replacement` are unused.
+    Encoding,
+}
+
 /// Determines the output colour.
 /// User can also filter by minimum severity level: e.g. don't show me Info-level messages.
 ///
@@ -2,7 +2,7 @@ use serde_json::json;
 
 use crate::report::errors::Errors;
 use crate::report::writer::kind_tag;
-use crate::report::LogReport;
+use crate::report::{FixKind, LogReport};
 
 /// Log the report in JSON format.
 pub fn log_report_json(errors: &mut Errors, report: &LogReport) {
@@ -24,6 +24,31 @@ pub fn log_report_json(errors: &mut Errors, report: &LogReport) {
             })
         })
         .collect();
+    // The fixes are included too, since they are suggestions that are known to be right.
+    let fixes = report.fixes.iter().map(|fix| (fix, true));
+    let suggestions: Vec<_> = fixes
+        .chain(report.suggestions.iter().map(|fix| (fix, false)))
+        .map(|(fix, applicable)| match fix.kind {
+            FixKind::Replace => json!({
+                "kind": fix.kind,
+                "path": fix.loc.pathname(),
+                "fullpath": fix.loc.fullpath(),
+                "linenr": if fix.loc.line == 0 { None } else { Some(fix.loc.line) },
+                "column": if fix.loc.column == 0 { None } else { Some(fix.loc.column) },
+                "length": fix.length,
+                "replacement": fix.replacement,
+                "applicable": applicable,
+            }),
+            // Saving the file in another encoding is not a text edit.
+            FixKind::Encoding => json!({
+                "kind": fix.kind,
+                "path": fix.loc.pathname(),
+                "fullpath": fix.loc.fullpath(),
+                "encoding": "UTF-8 with BOM",
+                "applicable": applicable,
+            }),
+        })
+        .collect();
     let report = json!({
         "severity": report.severity,
         "confidence": report.confidence,
@@ -31,6 +56,7 @@ pub fn log_report_json(errors: &mut Errors, report: &LogReport) {
         "message": &report.msg,
         "info": &report.info,
         "locations": pointers,
+        "suggestions": suggestions,
     });
 
     if let Err(e) = serde_json::to_writer_pretty(errors.output.get_mut(), &report) {
@@ -0,0 +1,159 @@
+//! Output of reports in the SARIF 2.1 format, for code scanning tools.
+//!
+//! See <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>
+
+use std::env::current_dir;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::fileset::FileKind;
+use crate::game::Game;
+use crate::report::errors::Errors;
+use crate::report::{Confidence, LogReport, PointedMessage, Severity};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Log all the reports as a single SARIF document.
+pub fn log_reports_sarif(errors: &mut Errors, reports: &[LogReport]) {
+    let mut rules: Vec<String> = reports.iter().map(|report| report.key.to_string()).collect();
+    rules.sort_unstable();
+    rules.dedup();
+
+    let results: Vec<_> = reports.iter().map(|report| result(report, &rules)).collect();
+    let sarif = json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": tool_name(),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules.iter().map(|key| json!({ "id": key })).collect::<Vec<_>>(),
+                },
+            },
+            // Our columns count characters, not UTF-16 code units which is the SARIF default.
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+
+    if let Err(e) = serde_json::to_writer_pretty(errors.output.get_mut(), &sarif) {
+        eprintln!("JSON error: {e:#}");
+    }
+    _ = writeln!(errors.output.get_mut());
+}
+
+fn tool_name() -> &'static str {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => "ck3-tiger",
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => "vic3-tiger",
+        #[cfg(feature = "imperator")]
+        Game::Imperator => "imperator-tiger",
+    }
+}
+
+fn result(report: &LogReport, rules: &[String]) -> Value {
+    let level = match report.severity {
+        Severity::Fatal | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Untidy | Severity::Tips => "note",
+    };
+    // SARIF ranks go from 0.0 to 100.0
+    let rank = match report.confidence {
+        Confidence::Weak => 25.0,
+        Confidence::Reasonable => 50.0,
+        Confidence::Strong => 90.0,
+    };
+    let mut text = report.msg.clone();
+    if let Some(info) = &report.info {
+        text.push('\n');
+        text.push_str(info);
+    }
+    let related: Vec<_> = report
+        .pointers
+        .iter()
+        .skip(1)
+        .enumerate()
+        .map(|(i, pointer)| {
+            let mut location = location(pointer);
+            location["id"] = json!(i + 1);
+            location
+        })
+        .collect();
+    let mut result = json!({
+        "ruleId": report.key,
+        "ruleIndex": rules.binary_search(&report.key.to_string()).ok(),
+        "level": level,
+        "rank": rank,
+        "message": { "text": text },
+        "locations": [location(report.primary())],
+    });
+    if !related.is_empty() {
+        result["relatedLocations"] = json!(related);
+    }
+    result
+}
+
+fn location(pointer: &PointedMessage) -> Value {
+    let loc = pointer.loc;
+    // Files of the mod are given relative to the mod root, which is usually the root of the
+    // repository too. Other files are given by their full path.
+    let artifact = if loc.kind == FileKind::Mod {
+        json!({ "uri": encode_uri(loc.pathname()), "uriBaseId": "%SRCROOT%" })
+    } else {
+        let mut fullpath = loc.fullpath().to_path_buf();
+        if fullpath.is_relative() {
+            if let Ok(cwd) = current_dir() {
+                fullpath = cwd.join(fullpath);
+            }
+        }
+        let path = encode_uri(&fullpath);
+        // Windows paths start with a drive letter rather than a `/`.
+        let sep = if path.starts_with('/') { "" } else { "/" };
+        json!({ "uri": format!("file://{sep}{path}") })
+    };
+    let mut physical = json!({ "artifactLocation": artifact });
+    // Line 0 means the report is about the file as a whole.
+    if loc.line > 0 {
+        let mut region = json!({ "startLine": loc.line });
+        if loc.column > 0 {
+            region["startColumn"] = json!(loc.column);
+            if pointer.length > 0 {
+                region["endColumn"] = json!(loc.column as usize + pointer.length);
+            }
+        }
+        physical["region"] = region;
+    }
+    let mut location = json!({ "physicalLocation": physical });
+    if let Some(msg) = &pointer.msg {
+        location["message"] = json!({ "text": msg });
+    }
+    location
+}
+
+/// Percent-encode a path for use in a URI, always using `/` as the separator.
+fn encode_uri(path: &Path) -> String {
+    let mut uri = String::new();
+    for (i, component) in path.iter().enumerate() {
+        let component = component.to_string_lossy();
+        if i > 0 && !uri.ends_with('/') {
+            uri.push('/');
+        }
+        for byte in component.bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~:".contains(&byte) {
+                uri.push(char::from(byte));
+            } else if byte == b'/' || byte == b'\\' {
+                // Root components of absolute paths.
+                uri.push('/');
+            } else {
+                _ = write!(uri, "%{byte:02X}");
+            }
+        }
+    }
+    uri
+}
@@ -182,6 +182,11 @@ impl Rivers {
     pub fn validate(&self, _data: &Everything) {
         // TODO: check image width and height against world defines
 
+        if self.entry.is_none() {
+            // There is no rivers.png. Nothing to check.
+            return;
+        }
+
         if self.color_type != Some(ColorType::Indexed) {
             let msg = "rivers.png should be in indexed color format (with 8-bit palette)";
             err(ErrorKey::ImageFormat).msg(msg).loc(self.entry.as_ref().unwrap()).push();
@@ -326,6 +331,10 @@ impl FileHandler<()> for Rivers {
         PathBuf::from("map_data/rivers.png")
     }
 
+    fn handles(&self, _entry: &FileEntry) -> bool {
+        true
+    }
+
     fn load_file(&self, _entry: &FileEntry, _parser: &ParserMemory) -> Option<()> {
         Some(())
     }
@@ -7,8 +7,10 @@ use bitflags::bitflags;
 use crate::context::ScopeContext;
 use crate::everything::Everything;
 use crate::game::Game;
+use crate::game_version::VersionRange;
 use crate::item::Item;
 use crate::report::{err, ErrorKey};
+use crate::script_docs;
 use crate::token::Token;
 
 /// vic3 needs more than 64 bits, but the others don't.
@@ -326,7 +328,8 @@ pub fn scope_to_scope(name: &Token, inscopes: Scopes) -> Option<(Scopes, Scopes)
     };
 
     let name_lc = name.as_str().to_ascii_lowercase();
-    if let scopes @ Some((from, _)) = scope_to_scope(&name_lc) {
+    let builtin = scope_to_scope(&name_lc);
+    if let scopes @ Some((from, _)) = script_docs::event_target(&name_lc, builtin) {
         #[cfg(feature = "vic3")]
         if Game::is_vic3() && name_lc == "type" {
             // Special case for "type" because it goes from specific scope types to specific
@@ -368,8 +371,10 @@ pub fn scope_to_scope(name: &Token, inscopes: Scopes) -> Option<(Scopes, Scopes)
         }
         scopes
     } else if let Some((version, explanation)) = scope_to_scope_removed(&name_lc) {
-        let msg = format!("`{name}` was removed in {version}");
-        err(ErrorKey::Removed).strong().msg(msg).info(explanation).loc(name).push();
+        if !VersionRange::until(version).includes_installed() {
+            let msg = format!("`{name}` was removed in {version}");
+            err(ErrorKey::Removed).strong().msg(msg).info(explanation).loc(name).push();
+        }
         return Some((Scopes::all(), Scopes::all_but_none()));
     } else {
         None
@@ -451,8 +456,10 @@ pub fn scope_iterator(
     if let scopes @ Some(_) = scope_iterator(&name_lc) {
         return scopes;
     } else if let Some((version, explanation)) = scope_iterator_removed(&name_lc) {
-        let msg = format!("`{name}` iterators were removed in {version}");
-        err(ErrorKey::Removed).strong().msg(msg).info(explanation).loc(name).push();
+        if !VersionRange::until(version).includes_installed() {
+            let msg = format!("`{name}` iterators were removed in {version}");
+            err(ErrorKey::Removed).strong().msg(msg).info(explanation).loc(name).push();
+        }
         return Some((Scopes::all(), Scopes::all()));
     } else if data.scripted_lists.exists(name.as_str()) {
         data.scripted_lists.validate_call(name, data, sc);
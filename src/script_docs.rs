@@ -0,0 +1,397 @@
+//! Load the definitions of effects, triggers, event targets, `on_action`s, and datafunctions from
+//! the logs that the game writes with the `script_docs` and `dump_data_types` console commands.
+//!
+//! The built-in tables are only updated with each tiger release. The logs let a mod be validated
+//! against a newer game version in the meantime. The logs don't say what kind of argument an effect
+//! or trigger takes, so for new ones that part is not validated.
+
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::OnceCell;
+
+use crate::data_types_log::{data_types_entries, DefinitionType};
+use crate::datatype::{
+    builtin_functions_map, builtin_global_functions_map, builtin_global_promotes_map,
+    builtin_promotes_map, Arg, Args, Datatype,
+};
+use crate::effect::Effect;
+use crate::helpers::TigerHashMap;
+use crate::scopes::Scopes;
+use crate::token::{leak, Token};
+use crate::trigger::Trigger;
+
+static SCRIPT_DOCS: OnceCell<ScriptDocs> = OnceCell::new();
+
+/// Datafunction arguments of unknown type, to slice from for new datafunctions.
+static UNKNOWN_ARGS: [Arg; 8] = [Arg::DType(Datatype::Unknown); 8];
+
+type FunctionsMap = TigerHashMap<&'static str, Vec<(Datatype, Args, Datatype)>>;
+
+#[derive(Debug, Default)]
+pub(crate) struct ScriptDocs {
+    /// If true, names that are not in a loaded log are treated as unknown even if the built-in
+    /// tables have them.
+    replace: bool,
+    effects: TigerHashMap<String, Scopes>,
+    triggers: TigerHashMap<String, Scopes>,
+    /// Input and output scopes of event targets such as `liege` or `capital_province`.
+    event_targets: TigerHashMap<String, (Scopes, Scopes)>,
+    /// The expected root scope of each `on_action` that the game calls.
+    on_actions: TigerHashMap<String, Scopes>,
+    /// The datafunction tables, already merged with the built-in ones. They are `None` if there
+    /// were no `data_types` logs.
+    pub(crate) global_promotes: Option<TigerHashMap<&'static str, (Args, Datatype)>>,
+    pub(crate) global_functions: Option<TigerHashMap<&'static str, (Args, Datatype)>>,
+    pub(crate) promotes: Option<FunctionsMap>,
+    pub(crate) functions: Option<FunctionsMap>,
+}
+
+impl ScriptDocs {
+    pub(crate) fn get() -> Option<&'static ScriptDocs> {
+        SCRIPT_DOCS.get()
+    }
+
+    fn effect(&self, name: &Token, builtin: Option<(Scopes, Effect)>) -> Option<(Scopes, Effect)> {
+        if self.effects.is_empty() {
+            return builtin;
+        }
+        // The logs also list the `every_`, `random_` and `ordered_` iterators. Those are left to
+        // the validation of iterators, so that their contents are still validated.
+        if builtin.is_none() && is_iterator(name.as_str()) {
+            return None;
+        }
+        match self.effects.get(&name.as_str().to_ascii_lowercase()) {
+            Some(scopes) => {
+                Some((*scopes, builtin.map_or(Effect::Unchecked, |(_, effect)| effect)))
+            }
+            None if self.replace => None,
+            None => builtin,
+        }
+    }
+}
+
+/// Load the game's logs from the directory `dir`, which is normally the game's `logs/script_docs`
+/// directory. The `data_types` logs are looked for in `dir/data_types` and in the sibling
+/// directory `logs/data_types`.
+///
+/// If `replace` is true, the logs replace the built-in tables instead of being merged over them.
+/// This should be called at most once, after [`crate::Game::set`] and before validation.
+///
+/// Returns the number of definitions read.
+pub fn load_script_docs(dir: &Path, replace: bool) -> Result<usize> {
+    let mut docs = ScriptDocs { replace, ..Default::default() };
+    let mut count = 0;
+
+    if let Some(content) = read_log(&dir.join("effects.log"))? {
+        for entry in entries(&content) {
+            docs.effects.insert(entry.name.to_ascii_lowercase(), entry.scopes("Supported Scopes"));
+        }
+        count += docs.effects.len();
+    }
+    if let Some(content) = read_log(&dir.join("triggers.log"))? {
+        for entry in entries(&content) {
+            docs.triggers.insert(entry.name.to_ascii_lowercase(), entry.scopes("Supported Scopes"));
+        }
+        count += docs.triggers.len();
+    }
+    if let Some(content) = read_log(&dir.join("event_targets.log"))? {
+        for entry in entries(&content) {
+            // The ones that require data are prefixes, such as `culture:`.
+            if entry.field("Requires Data") == Some("yes") {
+                continue;
+            }
+            let scopes = (entry.scopes("Input Scopes"), entry.scopes("Output Scopes"));
+            docs.event_targets.insert(entry.name.to_ascii_lowercase(), scopes);
+        }
+        count += docs.event_targets.len();
+    }
+    if let Some(content) = read_log(&dir.join("on_actions.log"))? {
+        for entry in entries(&content) {
+            if entry.field("Expected Scope").is_some() {
+                docs.on_actions.insert(entry.name.to_owned(), entry.scopes("Expected Scope"));
+            }
+        }
+        count += docs.on_actions.len();
+    }
+
+    let data_types_dir =
+        [dir.join("data_types"), dir.with_file_name("data_types")].into_iter().find(|d| d.is_dir());
+    if let Some(data_types_dir) = data_types_dir {
+        count += load_data_types(&mut docs, &data_types_dir)?;
+    }
+
+    if count == 0 {
+        bail!("no script_docs logs found in {}", dir.display());
+    }
+    if SCRIPT_DOCS.set(docs).is_err() {
+        bail!("script_docs logs were already loaded");
+    }
+    Ok(count)
+}
+
+fn read_log(path: &Path) -> Result<Option<String>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    // The logs are sometimes written with stray non-UTF-8 bytes in the descriptions.
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+/// One entry from a `script_docs` log, such as:
+/// ```text
+/// add_gold - Adds gold to a character
+/// add_gold = 100
+/// Supported Scopes: character
+/// ```
+struct Entry<'a> {
+    name: &'a str,
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Entry<'a> {
+    fn field(&self, name: &str) -> Option<&'a str> {
+        self.lines.iter().find_map(|line| {
+            line.strip_prefix(name).and_then(|rest| rest.strip_prefix(':')).map(str::trim)
+        })
+    }
+
+    /// Parse a field that lists scope types, such as `Supported Scopes: character, landed title`.
+    /// Scope types that tiger doesn't know are ignored. If none are known, or the field is
+    /// missing, any scope is allowed.
+    fn scopes(&self, name: &str) -> Scopes {
+        let mut scopes = Scopes::empty();
+        for part in self.field(name).unwrap_or("").split(',') {
+            if let Some(scope) = scope_from_log(part.trim()) {
+                scopes |= scope;
+            }
+        }
+        if scopes.is_empty() {
+            Scopes::all()
+        } else {
+            scopes
+        }
+    }
+}
+
+/// Split a `script_docs` log into its entries. They are separated by lines of dashes.
+fn entries(content: &str) -> Vec<Entry<'_>> {
+    let mut result = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+    for line in content.lines().map(str::trim_end).chain(std::iter::once("---")) {
+        if line.len() >= 3 && line.bytes().all(|c| c == b'-') {
+            if let Some((header, rest)) = lines.split_first() {
+                // Skip the "Effect Documentation:" title
+                if !header.ends_with("Documentation:") {
+                    let name = header.split_once(" - ").map_or(*header, |(name, _)| name);
+                    let name = name.trim().trim_end_matches(':');
+                    if !name.is_empty() && !name.contains(' ') {
+                        result.push(Entry { name, lines: rest.to_vec() });
+                    }
+                }
+            }
+            lines.clear();
+        } else if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    result
+}
+
+/// The logs use the same scope type names as tiger's messages, like `landed title`.
+fn scope_from_log(name: &str) -> Option<Scopes> {
+    let name = name.to_ascii_lowercase();
+    Scopes::all()
+        .iter()
+        .find(|scope| scope.to_string() == name)
+        .or_else(|| Scopes::from_snake_case(&name.replace(' ', "_")))
+}
+
+/// Read the `data_types_*.txt` files and merge them with the built-in datafunction tables.
+fn load_data_types(docs: &mut ScriptDocs, dir: &Path) -> Result<usize> {
+    let mut global_promotes = TigerHashMap::default();
+    let mut global_functions = TigerHashMap::default();
+    let mut promotes: FunctionsMap = TigerHashMap::default();
+    let mut functions: FunctionsMap = TigerHashMap::default();
+    let mut count = 0;
+
+    let mut paths: Vec<PathBuf> = read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let content =
+            read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        for entry in data_types_entries(&content) {
+            if entry.definition == DefinitionType::Type {
+                continue;
+            }
+            let args = Args::Args(&UNKNOWN_ARGS[..entry.nargs.min(UNKNOWN_ARGS.len())]);
+            let rtype = Datatype::from_str(entry.rtype).unwrap_or(Datatype::Unknown);
+            let name = leak(entry.name.to_owned());
+            let dtype =
+                entry.dtype.map(|dtype| Datatype::from_str(dtype).unwrap_or(Datatype::Unknown));
+            match (entry.definition, dtype) {
+                (DefinitionType::GlobalPromote, _) => {
+                    global_promotes.insert(name, (args, rtype));
+                }
+                (DefinitionType::GlobalFunction, _) => {
+                    global_functions.insert(name, (args, rtype));
+                }
+                (DefinitionType::Promote, Some(dtype)) => {
+                    promotes.entry(name).or_default().push((dtype, args, rtype));
+                }
+                (DefinitionType::Function, Some(dtype)) => {
+                    functions.entry(name).or_default().push((dtype, args, rtype));
+                }
+                _ => continue,
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return Ok(0);
+    }
+
+    // Root seems to work as well as ROOT
+    global_promotes.insert("Root", (Args::Args(&[]), Datatype::Scope));
+
+    docs.global_promotes =
+        Some(merge_globals(global_promotes, builtin_global_promotes_map(), docs.replace));
+    docs.global_functions =
+        Some(merge_globals(global_functions, builtin_global_functions_map(), docs.replace));
+    docs.promotes = Some(merge_functions(promotes, builtin_promotes_map(), docs.replace));
+    docs.functions = Some(merge_functions(functions, builtin_functions_map(), docs.replace));
+    Ok(count)
+}
+
+/// The built-in entry is kept if it agrees with the log, because its argument types are more
+/// precise than what the logs give.
+fn agrees(builtin: (Args, Datatype), new: (Args, Datatype)) -> bool {
+    let nargs = |args| match args {
+        Args::Unknown => None,
+        Args::Args(args) => Some(args.len()),
+    };
+    nargs(builtin.0) == nargs(new.0) && (new.1 == Datatype::Unknown || builtin.1 == new.1)
+}
+
+fn merge_globals(
+    mut new: TigerHashMap<&'static str, (Args, Datatype)>,
+    builtin: &TigerHashMap<&'static str, (Args, Datatype)>,
+    replace: bool,
+) -> TigerHashMap<&'static str, (Args, Datatype)> {
+    for (name, entry) in builtin {
+        if let Some(new_entry) = new.get_mut(name) {
+            if agrees(*entry, *new_entry) {
+                *new_entry = *entry;
+            }
+        } else if !replace {
+            new.insert(name, *entry);
+        }
+    }
+    new
+}
+
+fn merge_functions(mut new: FunctionsMap, builtin: &FunctionsMap, replace: bool) -> FunctionsMap {
+    for (name, entries) in builtin {
+        let new_entries = new.entry(name).or_default();
+        for (dtype, args, rtype) in entries.iter().copied() {
+            if let Some(new_entry) = new_entries.iter_mut().find(|(d, _, _)| *d == dtype) {
+                if agrees((args, rtype), (new_entry.1, new_entry.2)) {
+                    *new_entry = (dtype, args, rtype);
+                }
+            } else if !replace {
+                new_entries.push((dtype, args, rtype));
+            }
+        }
+    }
+    new.retain(|_, entries| !entries.is_empty());
+    new
+}
+
+/// Merge the logs' information about an effect with the built-in table's.
+/// `builtin` is the result of looking up `name` in the built-in table.
+pub(crate) fn effect(name: &Token, builtin: Option<(Scopes, Effect)>) -> Option<(Scopes, Effect)> {
+    match ScriptDocs::get() {
+        Some(docs) => docs.effect(name, builtin),
+        None => builtin,
+    }
+}
+
+/// Whether the name looks like a list iterator such as `every_vassal`.
+fn is_iterator(name: &str) -> bool {
+    name.split_once('_').is_some_and(|(it_type, _)| {
+        ["every", "random", "ordered", "any"].iter().any(|it| it_type.eq_ignore_ascii_case(it))
+    })
+}
+
+/// Merge the logs' information about a trigger with the built-in table's.
+/// `builtin` is the result of looking up `name` in the built-in table.
+pub(crate) fn trigger(
+    name: &Token,
+    builtin: Option<(Scopes, Trigger)>,
+) -> Option<(Scopes, Trigger)> {
+    let Some(docs) = ScriptDocs::get().filter(|docs| !docs.triggers.is_empty()) else {
+        return builtin;
+    };
+    match docs.triggers.get(&name.as_str().to_ascii_lowercase()) {
+        Some(scopes) => {
+            Some((*scopes, builtin.map_or(Trigger::UncheckedValue, |(_, trigger)| trigger)))
+        }
+        None if docs.replace => None,
+        None => builtin,
+    }
+}
+
+/// Merge the logs' information about an event target with the built-in table's.
+/// `name_lc` must be lowercase. `builtin` is the result of looking it up in the built-in table.
+pub(crate) fn event_target(
+    name_lc: &str,
+    builtin: Option<(Scopes, Scopes)>,
+) -> Option<(Scopes, Scopes)> {
+    let Some(docs) = ScriptDocs::get().filter(|docs| !docs.event_targets.is_empty()) else {
+        return builtin;
+    };
+    match docs.event_targets.get(name_lc) {
+        Some(scopes) => Some(*scopes),
+        None if docs.replace => None,
+        None => builtin,
+    }
+}
+
+/// Return the root scope that the game uses when calling the `on_action` `name`, if the logs
+/// have it.
+pub(crate) fn on_action(name: &str) -> Option<Scopes> {
+    ScriptDocs::get().and_then(|docs| docs.on_actions.get(name).copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::fileset::FileKind;
+    use crate::token::Loc;
+
+    #[test]
+    fn test_effect_iterators() {
+        let mut docs = ScriptDocs::default();
+        docs.effects.insert("every_vassal".to_owned(), Scopes::Character);
+        docs.effects.insert("new_effect".to_owned(), Scopes::Character);
+        let loc =
+            Loc::for_file(PathBuf::from("test.txt"), FileKind::Mod, PathBuf::from("test.txt"));
+        let token = |name: &str| Token::new(name, loc);
+
+        assert!(docs.effect(&token("every_vassal"), None).is_none());
+        assert!(matches!(
+            docs.effect(&token("new_effect"), None),
+            Some((Scopes::Character, Effect::Unchecked))
+        ));
+        docs.replace = true;
+        assert!(docs.effect(&token("every_vassal"), None).is_none());
+    }
+}
@@ -197,7 +197,8 @@ fn validate_inner(
 
             // Check for target = { script_value }
             sc.open_builder();
-            if validate_scope_chain(token, data, sc, matches!(cmp, Comparator::Equals(Question))) {
+            let qeq = matches!(cmp, Comparator::Equals(Question));
+            if validate_scope_chain(token, data, sc, qeq, false) {
                 if let Some(block) = bv.expect_block() {
                     sc.finalize_builder();
                     let vd = Validator::new(block, data);
@@ -0,0 +1,85 @@
+//! Loader for the FMOD strings banks (`sound/banks/*.strings.bank`) that come with the game and
+//! with mods. They list the names of the sound events defined in the sound banks.
+
+use std::fs::read;
+use std::path::PathBuf;
+
+use crate::fileset::{FileEntry, FileHandler};
+use crate::helpers::TigerHashSet;
+use crate::parse::ParserMemory;
+use crate::report::{warn, ErrorKey};
+
+const EVENT_PREFIX: &[u8] = b"event:/";
+
+#[derive(Clone, Debug, Default)]
+pub struct SoundBanks {
+    /// The lowercased `event:/` paths from all the strings banks.
+    events: TigerHashSet<String>,
+}
+
+impl SoundBanks {
+    /// Return the `event:/` paths in a strings bank, or an explanation of why it couldn't be read.
+    fn load_bank(entry: &FileEntry) -> Result<Vec<String>, String> {
+        let buffer = read(entry.fullpath()).map_err(|e| format!("{e:#}"))?;
+        // A bank is a RIFF file with form type "FEV ".
+        if buffer.len() < 12 || !buffer.starts_with(b"RIFF") || &buffer[8..12] != b"FEV " {
+            return Err("not an FMOD bank file".to_owned());
+        }
+        // The strings are stored as plain zero-terminated text in the string table chunk.
+        // Rather than decode the chunk structure, just look for the event paths.
+        let mut events = Vec::new();
+        let mut pos = 0;
+        while let Some(offset) = find(&buffer[pos..], EVENT_PREFIX) {
+            let start = pos + offset;
+            let len = buffer[start..]
+                .iter()
+                .position(|&c| c < b' ' || c == 0x7f)
+                .unwrap_or(buffer.len() - start);
+            if len > EVENT_PREFIX.len() {
+                events.push(String::from_utf8_lossy(&buffer[start..start + len]).to_lowercase());
+            }
+            pos = start + len.max(EVENT_PREFIX.len());
+        }
+        if events.is_empty() {
+            return Err("no event paths found in strings bank".to_owned());
+        }
+        Ok(events)
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.events.contains(&name.to_lowercase())
+    }
+}
+
+impl FileHandler<Vec<String>> for SoundBanks {
+    fn subpath(&self) -> PathBuf {
+        PathBuf::from("sound/banks")
+    }
+
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.filename().to_string_lossy().ends_with(".strings.bank")
+    }
+
+    fn load_file(&self, entry: &FileEntry, _parser: &ParserMemory) -> Option<Vec<String>> {
+        match Self::load_bank(entry) {
+            Ok(events) => Some(events),
+            Err(e) => {
+                let info = "sound events from this bank will be reported as missing";
+                warn(ErrorKey::ReadError)
+                    .msg(format!("could not read sound bank: {e}"))
+                    .info(info)
+                    .loc(entry)
+                    .push();
+                None
+            }
+        }
+    }
+
+    fn handle_file(&mut self, _entry: &FileEntry, events: Vec<String>) {
+        self.events.extend(events);
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
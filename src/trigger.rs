@@ -12,15 +12,19 @@ use crate::date::Date;
 use crate::desc::validate_desc;
 use crate::everything::Everything;
 use crate::game::Game;
-use crate::helpers::stringify_choices;
+use crate::game_version::unknown_token_confidence;
+#[cfg(any(feature = "ck3", feature = "vic3"))]
+use crate::game_version::VersionRange;
+use crate::helpers::{did_you_mean, stringify_choices};
 use crate::item::Item;
 use crate::lowercase::Lowercase;
 #[cfg(feature = "vic3")]
 use crate::modif::{verify_modif_exists, ModifKinds};
-use crate::report::{err, fatal, tips, warn, ErrorKey, Severity};
+use crate::report::{err, fatal, tips, warn, will_maybe_log, ErrorKey, Severity};
 use crate::scopes::{
     needs_prefix, scope_iterator, scope_prefix, scope_to_scope, ArgumentValue, Scopes,
 };
+use crate::script_docs;
 use crate::script_value::validate_script_value;
 use crate::token::{Loc, Token};
 use crate::tooltipped::Tooltipped;
@@ -46,7 +50,19 @@ pub fn scope_trigger(name: &Token, data: &Everything) -> Option<(Scopes, Trigger
         #[cfg(feature = "imperator")]
         Game::Imperator => crate::imperator::tables::triggers::scope_trigger,
     };
-    scope_trigger(name, data)
+    script_docs::trigger(name, scope_trigger(name, data))
+}
+
+/// Return the names of the built-in triggers that are spelled similarly to `name`.
+pub fn similar_triggers(name: &str) -> Vec<&'static str> {
+    match Game::game() {
+        #[cfg(feature = "ck3")]
+        Game::Ck3 => crate::ck3::tables::triggers::similar_triggers(name),
+        #[cfg(feature = "vic3")]
+        Game::Vic3 => crate::vic3::tables::triggers::similar_triggers(name),
+        #[cfg(feature = "imperator")]
+        Game::Imperator => crate::imperator::tables::triggers::similar_triggers(name),
+    }
 }
 
 /// The standard interface to trigger validation. Validates a trigger in the given [`ScopeContext`].
@@ -471,7 +487,23 @@ pub fn validate_trigger_key_bv(
                 } else {
                     // TODO: warn if trying to use iterator here
                     let msg = format!("unknown token `{part}`");
-                    err(ErrorKey::UnknownField).msg(msg).loc(part).push();
+                    let conf = unknown_token_confidence();
+                    let similar = if part_flags.contains(PartFlags::Last)
+                        && will_maybe_log(part, ErrorKey::UnknownField)
+                    {
+                        similar_triggers(part.as_str())
+                    } else {
+                        Vec::new()
+                    };
+                    let mut builder = err(ErrorKey::UnknownField)
+                        .conf(conf)
+                        .msg(msg)
+                        .opt_info(did_you_mean(&similar))
+                        .loc(part);
+                    for trigger in similar {
+                        builder = builder.suggest(part, trigger);
+                    }
+                    builder.push();
                     sc.close();
                     return side_effects;
                 }
@@ -963,8 +995,11 @@ fn match_trigger_bv(
             // TODO: time_of_year
         }
         #[cfg(any(feature = "ck3", feature = "vic3"))]
-        Trigger::Removed(msg, info) => {
-            err(ErrorKey::Removed).msg(*msg).info(*info).loc(name).push();
+        Trigger::Removed(version, explanation) => {
+            if !VersionRange::until(version).includes_installed() {
+                let msg = format!("`{name}` was removed in {version}");
+                err(ErrorKey::Removed).msg(msg).info(*explanation).loc(name).push();
+            }
         }
         Trigger::UncheckedValue => {
             bv.expect_value();
@@ -1087,7 +1122,13 @@ pub fn validate_target_ok_this(
 
                     // TODO: warn if trying to use iterator here
                     let msg = format!("unknown token `{part}`");
-                    err(ErrorKey::UnknownField).msg(msg).opt_info(opt_info).loc(part).push();
+                    let conf = unknown_token_confidence();
+                    err(ErrorKey::UnknownField)
+                        .conf(conf)
+                        .msg(msg)
+                        .opt_info(opt_info)
+                        .loc(part)
+                        .push();
                     sc.close();
                     return Scopes::all();
                 }
@@ -1415,7 +1456,8 @@ pub fn validate_argument(
         validate_argument_scope(part_flags, entry, func, arg, data, sc);
     } else {
         let msg = format!("unknown token `{func}`");
-        err(ErrorKey::Validation).msg(msg).loc(func).push();
+        let conf = unknown_token_confidence();
+        err(ErrorKey::Validation).conf(conf).msg(msg).loc(func).push();
     }
 }
 
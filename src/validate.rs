@@ -10,11 +10,14 @@ use crate::ck3::validate::{
 };
 use crate::context::ScopeContext;
 use crate::data::scripted_modifiers::ScriptedModifier;
+use crate::effect::similar_effects;
 use crate::everything::Everything;
 use crate::game::Game;
+use crate::game_version::unknown_token_confidence;
+use crate::helpers::did_you_mean;
 use crate::item::Item;
 use crate::lowercase::Lowercase;
-use crate::report::{err, fatal, report, warn, Confidence, ErrorKey, Severity};
+use crate::report::{err, fatal, report, warn, will_maybe_log, Confidence, ErrorKey, Severity};
 #[cfg(feature = "ck3")]
 use crate::scopes::Scopes;
 use crate::scopes::{scope_prefix, scope_to_scope};
@@ -596,6 +599,7 @@ pub fn validate_scripted_modifier_call(
     data: &Everything,
     sc: &mut ScopeContext,
 ) {
+    data.mark_referenced(Item::ScriptedModifier, key);
     match bv {
         BV::Value(token) => {
             if !modifier.macro_parms().is_empty() {
@@ -664,11 +668,14 @@ pub fn validate_ai_chance(bv: &BV, data: &Everything, sc: &mut ScopeContext) {
 /// The caller is expected to have done `sc.open_builder()` before calling and then do `sc.close()` after calling.
 /// Returns true iff validation was complete.
 /// `qeq` is true if the scope chain is to the left of a ?= operator.
+/// `is_effect` is true if the scope chain is where an effect could be, which is used to suggest
+/// effect names when the last part is unknown.
 pub fn validate_scope_chain(
     token: &Token,
     data: &Everything,
     sc: &mut ScopeContext,
     qeq: bool,
+    is_effect: bool,
 ) -> bool {
     let part_vec = partition(token);
     for i in 0..part_vec.len() {
@@ -712,7 +719,24 @@ pub fn validate_scope_chain(
                     sc.replace(outscope, part.clone());
                 } else {
                     let msg = format!("unknown token `{part}`");
-                    err(ErrorKey::UnknownField).msg(msg).loc(part).push();
+                    let conf = unknown_token_confidence();
+                    let similar = if is_effect
+                        && part_flags.contains(PartFlags::Last)
+                        && will_maybe_log(part, ErrorKey::UnknownField)
+                    {
+                        similar_effects(part.as_str())
+                    } else {
+                        Vec::new()
+                    };
+                    let mut builder = err(ErrorKey::UnknownField)
+                        .conf(conf)
+                        .msg(msg)
+                        .opt_info(did_you_mean(&similar))
+                        .loc(part);
+                    for effect in similar {
+                        builder = builder.suggest(part, effect);
+                    }
+                    builder.push();
                     return false;
                 }
             }
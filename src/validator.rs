@@ -8,12 +8,12 @@ use crate::context::ScopeContext;
 use crate::date::Date;
 use crate::effect::validate_effect_internal;
 use crate::everything::Everything;
-use crate::helpers::{dup_assign_error, TigerHashSet};
+use crate::helpers::{did_you_mean, dup_assign_error, similar_names, TigerHashSet};
 use crate::item::Item;
 use crate::lowercase::Lowercase;
 #[cfg(feature = "ck3")]
 use crate::report::fatal;
-use crate::report::{report, ErrorKey, Severity};
+use crate::report::{report, will_maybe_log, ErrorKey, Severity};
 use crate::scopes::Scopes;
 #[cfg(any(feature = "ck3", feature = "vic3"))]
 use crate::script_value::validate_script_value_no_breakdown;
@@ -90,6 +90,10 @@ pub struct Validator<'a> {
     data: &'a Everything,
     /// Fields that have been requested so far
     known_fields: Vec<&'a str>,
+    /// Field names that have been asked about, each followed by a newline.
+    /// They are kept in one buffer so that remembering a name doesn't allocate.
+    /// Used to suggest names for unknown fields.
+    expected_fields: String,
     /// Whether loose tokens are expected
     accepted_tokens: bool,
     /// Whether subblocks are expected
@@ -107,6 +111,9 @@ pub struct Validator<'a> {
     /// As an exception, `Fatal` severity reports will still always be logged as `Fatal`.
     /// TODO: pass this down to all the helper functions
     max_severity: Severity,
+    /// The name under which `field_event` and `field_action` record their calls in the event
+    /// graph, if not the field name.
+    event_call_label: Option<&'a str>,
 }
 
 impl Debug for Validator<'_> {
@@ -115,6 +122,7 @@ impl Debug for Validator<'_> {
         f.debug_struct("Validator")
             .field("block", &self.block)
             .field("known_fields", &self.known_fields)
+            .field("expected_fields", &self.expected_fields)
             .field("accepted_tokens", &self.accepted_tokens)
             .field("accepted_blocks", &self.accepted_blocks)
             .field("accepted_block_fields", &self.accepted_block_fields)
@@ -122,6 +130,7 @@ impl Debug for Validator<'_> {
             .field("case_sensitive", &self.case_sensitive)
             .field("allow_questionmark_equals", &self.allow_questionmark_equals)
             .field("max_severity", &self.max_severity)
+            .field("event_call_label", &self.event_call_label)
             .finish()
     }
 }
@@ -134,6 +143,7 @@ impl<'a> Validator<'a> {
             block,
             data,
             known_fields: Vec::new(),
+            expected_fields: String::new(),
             accepted_tokens: false,
             accepted_blocks: false,
             accepted_block_fields: false,
@@ -141,6 +151,7 @@ impl<'a> Validator<'a> {
             case_sensitive: true,
             allow_questionmark_equals: false,
             max_severity: Severity::Fatal,
+            event_call_label: None,
         }
     }
 
@@ -160,6 +171,13 @@ impl<'a> Validator<'a> {
         self.max_severity = max_severity;
     }
 
+    /// Record the calls made by `field_event` and `field_action` under `label` in the event
+    /// graph, instead of under the field name. This is for blocks that belong to an effect, such
+    /// as the block of `trigger_event`.
+    pub fn set_event_call_label(&mut self, label: &'a str) {
+        self.event_call_label = Some(label);
+    }
+
     /// Require field `name` to be present in the block, and warn if it isn't there.
     /// Returns true iff the field is present.
     pub fn req_field(&mut self, name: &str) -> bool {
@@ -238,11 +256,25 @@ impl<'a> Validator<'a> {
         let sev = Severity::Error.at_most(self.max_severity);
         self.multi_field_check(name, |key, _| {
             let msg = format!("`{name}` has been replaced by {replaced_by}");
-            report(ErrorKey::Validation, sev).msg(msg).loc(key).push();
+            let builder = report(ErrorKey::Validation, sev).msg(msg).loc(key);
+            // Only a plain field name can be swapped in mechanically.
+            if replaced_by.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                builder.fix(key, replaced_by).push();
+            } else {
+                builder.push();
+            }
         });
     }
 
+    /// Remember `name` for "did you mean" suggestions. This is on the path of every field lookup,
+    /// so the comparisons are left to [`Validator::report_unknown_field`].
+    fn note_expected(&mut self, name: &str) {
+        self.expected_fields.push_str(name);
+        self.expected_fields.push('\n');
+    }
+
     fn check_key(&mut self, name: &str) -> bool {
+        self.note_expected(name);
         for Field(key, _, _) in self.block.iter_fields() {
             if (self.case_sensitive && key.is(name))
                 || (!self.case_sensitive && key.lowercase_is(name))
@@ -259,6 +291,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Token, &BV),
     {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if (self.case_sensitive && key.is(name))
                 || (!self.case_sensitive && key.lowercase_is(name))
@@ -280,6 +313,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Token, &BV),
     {
         let mut found = false;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if (self.case_sensitive && key.is(name))
                 || (!self.case_sensitive && key.lowercase_is(name))
@@ -311,6 +345,7 @@ impl<'a> Validator<'a> {
     /// TODO: replace this with a `field_validated` variant.
     pub fn field_any_cmp(&mut self, name: &str) -> Option<&BV> {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, _, bv) in self.block.iter_fields() {
             if (self.case_sensitive && key.is(name))
                 || (!self.case_sensitive && key.lowercase_is(name))
@@ -335,6 +370,7 @@ impl<'a> Validator<'a> {
     pub fn field_value(&mut self, name: &str) -> Option<&Token> {
         let mut found = None;
         let mut result = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if (self.case_sensitive && key.is(name))
                 || (!self.case_sensitive && key.lowercase_is(name))
@@ -407,8 +443,10 @@ impl<'a> Validator<'a> {
     pub fn field_action(&mut self, name: &str, sc: &ScopeContext) -> bool {
         let sev = self.max_severity;
         let data = &self.data;
+        let label = self.event_call_label.unwrap_or(name);
         self.field_check(name, |_, bv| {
             if let Some(token) = bv.expect_value() {
+                self.data.add_event_call(sc, label, Item::OnAction, token);
                 self.data.verify_exists_max_sev(Item::OnAction, token, sev);
                 if let Some(mut action_sc) = sc.root_for_action(token) {
                     self.data.on_actions.validate_call(token, data, &mut action_sc);
@@ -425,8 +463,10 @@ impl<'a> Validator<'a> {
     pub fn field_event(&mut self, name: &str, sc: &mut ScopeContext) -> bool {
         let sev = self.max_severity;
         let data = &self.data;
+        let label = self.event_call_label.unwrap_or(name);
         self.field_check(name, |_, bv| {
             if let Some(token) = bv.expect_value() {
+                self.data.add_event_call(sc, label, Item::Event, token);
                 self.data.verify_exists_max_sev(Item::Event, token, sev);
                 self.data.events.check_scope(token, sc);
                 if let Some(mut event_sc) = sc.root_for_event(token) {
@@ -1012,6 +1052,7 @@ impl<'a> Validator<'a> {
     /// Just like [`Validator::field_value`], but expect any number of `name` fields in the block.
     pub fn multi_field_value(&mut self, name: &str) -> Vec<&Token> {
         let mut vec = Vec::new();
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1026,6 +1067,7 @@ impl<'a> Validator<'a> {
 
     /// Just like [`Validator::field_item`], but expect any number of `name` fields in the block.
     pub fn multi_field_item(&mut self, name: &str, itype: Item) {
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1040,6 +1082,7 @@ impl<'a> Validator<'a> {
     /// Just like [`Validator::field_any_cmp`], but expect any number of `name` fields in the block.
     pub fn multi_field_any_cmp(&mut self, name: &str) -> bool {
         let mut found = false;
+        self.note_expected(name);
         for Field(key, _, _) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1058,6 +1101,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&BV, &Everything),
     {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1078,6 +1122,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Token, &BV, &Everything),
     {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1133,6 +1178,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&BV, &Everything),
     {
         let mut found = false;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1151,6 +1197,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Token, &BV, &Everything),
     {
         let mut found = false;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1182,6 +1229,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Block, &Everything),
     {
         let mut found = false;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1217,6 +1265,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Block, &Everything),
     {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1239,6 +1288,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Token, &Block, &Everything),
     {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1261,6 +1311,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Block, &Everything, &mut ScopeContext),
     {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1284,6 +1335,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Token, &Block, &Everything),
     {
         let mut found = false;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1327,6 +1379,7 @@ impl<'a> Validator<'a> {
     where
         F: FnMut(&Block, &Everything, &mut ScopeContext),
     {
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1355,6 +1408,7 @@ impl<'a> Validator<'a> {
         F: FnMut(&Block, &Everything, &mut ScopeContext),
     {
         let mut found = None;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1377,6 +1431,7 @@ impl<'a> Validator<'a> {
     #[cfg(feature = "ck3")] // vic3 happens not to use; silence dead code warning
     pub fn multi_field_block(&mut self, name: &str) -> bool {
         let mut found = false;
+        self.note_expected(name);
         for Field(key, cmp, bv) in self.block.iter_fields() {
             if key.is(name) {
                 self.known_fields.push(key.as_str());
@@ -1728,18 +1783,14 @@ impl<'a> Validator<'a> {
                     BV::Value(_) => {
                         if !self.accepted_value_fields && !self.known_fields.contains(&key.as_str())
                         {
-                            let msg = format!("unknown field `{key}`");
-                            let sev = Severity::Error.at_most(self.max_severity);
-                            report(ErrorKey::UnknownField, sev).weak().msg(msg).loc(key).push();
+                            self.report_unknown_field(key);
                             warned = true;
                         }
                     }
                     BV::Block(_) => {
                         if !self.accepted_block_fields && !self.known_fields.contains(&key.as_str())
                         {
-                            let msg = format!("unknown field `{key}`");
-                            let sev = Severity::Error.at_most(self.max_severity);
-                            report(ErrorKey::UnknownField, sev).weak().msg(msg).loc(key).push();
+                            self.report_unknown_field(key);
                             warned = true;
                         }
                     }
@@ -1766,6 +1817,27 @@ impl<'a> Validator<'a> {
         warned
     }
 
+    /// Report a field that wasn't expected, and suggest the expected fields that are spelled
+    /// similarly.
+    fn report_unknown_field(&self, key: &Token) {
+        let msg = format!("unknown field `{key}`");
+        let sev = Severity::Error.at_most(self.max_severity);
+        let similar = if will_maybe_log(key, ErrorKey::UnknownField) {
+            similar_names(key.as_str(), self.expected_fields.lines())
+        } else {
+            Vec::new()
+        };
+        let mut builder = report(ErrorKey::UnknownField, sev)
+            .weak()
+            .msg(msg)
+            .opt_info(did_you_mean(&similar))
+            .loc(key);
+        for field in similar {
+            builder = builder.suggest(key, field);
+        }
+        builder.push();
+    }
+
     fn expect_eq_qeq(&self, key: &Token, cmp: Comparator) {
         #[allow(clippy::collapsible_else_if)]
         if self.allow_questionmark_equals {
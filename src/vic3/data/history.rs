@@ -1,13 +1,16 @@
 use std::path::PathBuf;
 
-use crate::block::Block;
+use crate::block::{Block, Field, BV};
 use crate::context::ScopeContext;
 use crate::effect::validate_effect;
 use crate::everything::Everything;
 use crate::fileset::{FileEntry, FileHandler};
 use crate::helpers::TigerHashMap;
+use crate::item::Item;
+use crate::lowercase::Lowercase;
 use crate::parse::ParserMemory;
 use crate::pdxfile::PdxFile;
+use crate::report::{err, warn, ErrorKey};
 use crate::scopes::Scopes;
 use crate::token::Token;
 use crate::tooltipped::Tooltipped;
@@ -15,6 +18,9 @@ use crate::tooltipped::Tooltipped;
 /// The history files in Vic3 are fairly simple. Files under `common/history/` have `keyword = { effect... }` as top-level blocks,
 /// where the effects from the same keywords are all added together. The keywords seem to be arbitrary, except for GLOBAL which
 /// is documented to go last.
+///
+/// After validation, the effects that set up the starting states, laws, institutions, pops and
+/// buildings are simulated to find history entries that contradict each other.
 
 #[derive(Clone, Debug, Default)]
 pub struct History {
@@ -38,11 +44,20 @@ impl History {
         names.sort_unstable();
         names.push("GLOBAL");
 
-        for name in names {
+        for name in &names {
             if let Some(item) = self.history.get(name) {
                 item.validate(data);
             }
         }
+
+        let mut simulation = Simulation::default();
+        for name in &names {
+            if let Some(item) = self.history.get(name) {
+                simulation.keyword = Some(&item.key);
+                simulation.walk(&item.block, &HistoryScope::None, false, data);
+            }
+        }
+        simulation.check_placements();
     }
 }
 
@@ -52,10 +67,6 @@ impl FileHandler<Block> for History {
     }
 
     fn load_file(&self, entry: &FileEntry, parser: &ParserMemory) -> Option<Block> {
-        if !entry.filename().to_string_lossy().ends_with(".txt") {
-            return None;
-        }
-
         PdxFile::read(entry, parser)
     }
 
@@ -82,3 +93,227 @@ impl HistoryEffect {
         validate_effect(&self.block, data, &mut sc, Tooltipped::No);
     }
 }
+
+/// What a history effect is applied to.
+#[derive(Clone, Debug)]
+enum HistoryScope {
+    None,
+    StateRegion(Token),
+    Country(Token),
+}
+
+/// Where something was set up by the history, for pointing at it in reports.
+#[derive(Clone, Copy, Debug)]
+struct Setup<'a> {
+    keyword: &'a Token,
+    token: &'a Token,
+    /// Whether this was inside an `if` or similar block, so it may not happen at all.
+    conditional: bool,
+}
+
+/// A record of what the history effects have set up so far.
+///
+/// Effects inside `if`, `else_if` and `else` blocks may or may not happen, so they aren't reported
+/// as contradicting other effects. States created in them do count as existing, though.
+#[derive(Debug, Default)]
+struct Simulation<'a> {
+    /// The history keyword whose effects are being simulated.
+    keyword: Option<&'a Token>,
+    /// The `create_state` effects, per state region and owning country tag.
+    states: TigerHashMap<(&'static str, &'static str), Setup<'a>>,
+    /// The `create_state` effect that gave each province to a country.
+    provinces: TigerHashMap<Lowercase<'static>, Setup<'a>>,
+    /// The activated laws, per country and law group.
+    laws: TigerHashMap<(&'static str, &'static str), Setup<'a>>,
+    /// The institution investment levels, per country and institution.
+    institutions: TigerHashMap<(&'static str, &'static str), Setup<'a>>,
+    /// The `region_state:TAG` scopes in which pops or buildings are created, with the state region
+    /// they are in.
+    placements: Vec<(Token, &'a Token)>,
+}
+
+impl<'a> Simulation<'a> {
+    fn setup(&self, token: &'a Token, conditional: bool) -> Setup<'a> {
+        let keyword = self.keyword.expect("keyword set before walk");
+        Setup { keyword, token, conditional }
+    }
+
+    fn walk(
+        &mut self,
+        block: &'a Block,
+        scope: &HistoryScope,
+        conditional: bool,
+        data: &Everything,
+    ) {
+        for Field(key, _, bv) in block.iter_fields() {
+            match bv {
+                BV::Value(value) => {
+                    if key.is("activate_law") {
+                        if let HistoryScope::Country(country) = scope {
+                            self.activate_law(country, value, conditional, data);
+                        }
+                    }
+                }
+                BV::Block(block) => self.walk_block(key, block, scope, conditional, data),
+            }
+        }
+    }
+
+    fn walk_block(
+        &mut self,
+        key: &'a Token,
+        block: &'a Block,
+        scope: &HistoryScope,
+        conditional: bool,
+        data: &Everything,
+    ) {
+        if key.is("if") || key.is("else_if") || key.is("else") {
+            self.walk(block, scope, true, data);
+        } else if let Some(state_region) = key.strip_prefix("s:") {
+            self.walk(block, &HistoryScope::StateRegion(state_region), conditional, data);
+        } else if let Some(country) = key.strip_prefix("c:") {
+            self.walk(block, &HistoryScope::Country(country), conditional, data);
+        } else if let HistoryScope::StateRegion(state_region) = scope {
+            if key.is("create_state") {
+                self.create_state(state_region, block, conditional);
+            } else if key.starts_with("region_state:")
+                && (block.has_key_recursive("create_pop")
+                    || block.has_key_recursive("create_building"))
+            {
+                self.placements.push((state_region.clone(), key));
+            }
+        } else if let HistoryScope::Country(country) = scope {
+            if key.is("set_institution_investment_level") {
+                self.set_institution(country, block, conditional);
+            }
+        }
+    }
+
+    fn create_state(&mut self, state_region: &Token, block: &'a Block, conditional: bool) {
+        let Some(country) = block.get_field_value("country") else {
+            return;
+        };
+        let tag = country.as_str().strip_prefix("c:").unwrap_or(country.as_str());
+        let setup = self.setup(country, conditional);
+        if let Some(&first) = self.states.get(&(state_region.as_str(), tag)) {
+            if !first.conditional && !conditional {
+                let msg = format!("{state_region} is given to {tag} more than once");
+                let info = "a country can only have one state in each state region";
+                err(ErrorKey::History)
+                    .msg(msg)
+                    .info(info)
+                    .loc(country)
+                    .loc_msg(first.token, format!("first given here, under `{}`", first.keyword))
+                    .push();
+                return;
+            }
+        } else {
+            self.states.insert((state_region.as_str(), tag), setup);
+        }
+
+        if let Some(provinces) = block.get_field_block("owned_provinces") {
+            for province in provinces.iter_values() {
+                let setup = self.setup(province, conditional);
+                let lc = Lowercase::new(province.as_str());
+                if let Some(&first) = self.provinces.get(&lc) {
+                    if !first.conditional && !conditional {
+                        let msg = format!("province {province} is given to more than one state");
+                        err(ErrorKey::History)
+                            .msg(msg)
+                            .loc(province)
+                            .loc_msg(
+                                first.token,
+                                format!("also given here, under `{}`", first.keyword),
+                            )
+                            .push();
+                    }
+                } else {
+                    self.provinces.insert(lc, setup);
+                }
+            }
+        }
+    }
+
+    fn activate_law(
+        &mut self,
+        country: &Token,
+        law: &'a Token,
+        conditional: bool,
+        data: &Everything,
+    ) {
+        if conditional {
+            return;
+        }
+        let name = law.as_str().strip_prefix("law_type:").unwrap_or(law.as_str());
+        let Some(group) = data
+            .database
+            .get_key_block(Item::LawType, name)
+            .and_then(|(_, block)| block.get_field_value("group"))
+        else {
+            return;
+        };
+        let setup = self.setup(law, conditional);
+        if let Some(first) = self.laws.insert((country.as_str(), group.as_str()), setup) {
+            let first_name =
+                first.token.as_str().strip_prefix("law_type:").unwrap_or(first.token.as_str());
+            if first_name != name {
+                let msg = format!(
+                    "{country} activates both `{first_name}` and `{name}` from law group `{group}`"
+                );
+                let info = "only one law from each group can be active";
+                warn(ErrorKey::History)
+                    .msg(msg)
+                    .info(info)
+                    .loc(law)
+                    .loc_msg(first.token, format!("also activated here, under `{}`", first.keyword))
+                    .push();
+            }
+        }
+    }
+
+    fn set_institution(&mut self, country: &Token, block: &'a Block, conditional: bool) {
+        if conditional {
+            return;
+        }
+        let (Some(institution), Some(level)) =
+            (block.get_field_value("institution"), block.get_field_value("level"))
+        else {
+            return;
+        };
+        let setup = self.setup(level, conditional);
+        if let Some(first) =
+            self.institutions.insert((country.as_str(), institution.as_str()), setup)
+        {
+            if first.token.as_str() != level.as_str() {
+                let msg = format!(
+                    "{country} sets the investment level of `{institution}` to both {} and {level}",
+                    first.token
+                );
+                warn(ErrorKey::History)
+                    .msg(msg)
+                    .loc(level)
+                    .loc_msg(first.token, format!("also set here, under `{}`", first.keyword))
+                    .push();
+            }
+        }
+    }
+
+    /// Report pops and buildings that are created in a state region where the country doesn't own
+    /// a state.
+    ///
+    /// The order in which the history keywords are executed isn't known, so this checks against all
+    /// the states that the history creates, rather than only those created before the pops.
+    fn check_placements(&self) {
+        if self.states.is_empty() {
+            return;
+        }
+        for (state_region, key) in &self.placements {
+            let tag = key.as_str().strip_prefix("region_state:").unwrap_or(key.as_str());
+            if !self.states.contains_key(&(state_region.as_str(), tag)) {
+                let msg = format!("pops or buildings are created in {state_region} for {tag}, but {tag} has no state there");
+                let info = "this is a common cause of crashes at game start";
+                err(ErrorKey::History).msg(msg).info(info).loc(*key).push();
+            }
+        }
+    }
+}
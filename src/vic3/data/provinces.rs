@@ -1,9 +1,10 @@
 use std::path::PathBuf;
 
-use image::{DynamicImage, Rgb};
+use image::{DynamicImage, Rgb, RgbImage};
 
+use crate::data::province_map::ProvinceMap;
 use crate::everything::Everything;
-use crate::fileset::{FileEntry, FileHandler};
+use crate::fileset::{FileEntry, FileHandler, FileKind};
 use crate::helpers::TigerHashSet;
 use crate::item::Item;
 use crate::parse::ParserMemory;
@@ -17,6 +18,9 @@ pub struct Vic3Provinces {
 
     /// Kept and used for error reporting.
     provinces_png: Option<FileEntry>,
+
+    /// The provinces.png image, kept until `finalize` to check the geometry of the map.
+    image: Option<RgbImage>,
 }
 
 impl Vic3Provinces {
@@ -58,6 +62,10 @@ impl FileHandler<DynamicImage> for Vic3Provinces {
         PathBuf::from("map_data/provinces.png")
     }
 
+    fn handles(&self, entry: &FileEntry) -> bool {
+        entry.path().components().count() == 2
+    }
+
     fn load_file(&self, entry: &FileEntry, _parser: &ParserMemory) -> Option<DynamicImage> {
         if entry.path().components().count() == 2 {
             let img = match image::open(entry.fullpath()) {
@@ -89,6 +97,21 @@ impl FileHandler<DynamicImage> for Vic3Provinces {
             for pixel in img.pixels() {
                 self.colors.insert(*pixel);
             }
+            self.image = Some(img);
+        }
+    }
+
+    /// Check the geometry of the map, if the mod has its own provinces.png.
+    /// The base game's map is assumed to be fine, and checking a full-size map takes a while.
+    fn finalize(&mut self) {
+        let (Some(entry), Some(img)) = (&self.provinces_png, self.image.take()) else {
+            return;
+        };
+        if entry.kind() == FileKind::Mod {
+            let map = ProvinceMap::new(img);
+            map.check_areas(entry, |Rgb([r, g, b])| {
+                Some(format!("province x{r:02X}{g:02X}{b:02X}"))
+            });
         }
     }
 }
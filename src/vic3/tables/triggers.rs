@@ -1,7 +1,7 @@
 use once_cell::sync::Lazy;
 
 use crate::everything::Everything;
-use crate::helpers::TigerHashMap;
+use crate::helpers::{similar_names, TigerHashMap};
 use crate::item::Item;
 use crate::scopes::*;
 use crate::token::Token;
@@ -15,6 +15,12 @@ pub fn scope_trigger(name: &Token, _data: &Everything) -> Option<(Scopes, Trigge
     TRIGGER_MAP.get(&*name_lc).copied()
 }
 
+/// Return the names of the triggers that are spelled similarly to `name`.
+pub fn similar_triggers(name: &str) -> Vec<&'static str> {
+    let triggers = TRIGGER.iter().filter(|(_, _, trigger)| !matches!(trigger, Removed(_, _)));
+    similar_names(name, triggers.map(|&(_, trigger, _)| trigger))
+}
+
 static TRIGGER_MAP: Lazy<TigerHashMap<&'static str, (Scopes, Trigger)>> = Lazy::new(|| {
     let mut hash = TigerHashMap::default();
     for (from, s, trigger) in TRIGGER.iter().copied() {
@@ -609,7 +615,7 @@ const TRIGGER: &[(Scopes, &str, Trigger)] = &[
     (
         Scopes::Country,
         "is_adjacent",
-        Removed("removed in 1.5", "replaced with is_adjacent_to_country and is_adjacent_to_state"),
+        Removed("1.5", "replaced with is_adjacent_to_country and is_adjacent_to_state"),
     ),
     (Scopes::Country, "is_adjacent_to_country", Scope(Scopes::Country)),
     (Scopes::Country, "is_adjacent_to_state", Scope(Scopes::State)),
@@ -1,8 +1,9 @@
 use lazy_static::lazy_static;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
-use tiger_lib::{take_reports, Everything, LogReport};
+use tiger_lib::{take_reports, Changes, Everything, LogReport};
 
 lazy_static! {
     static ref TEST_MUTEX: Mutex<()> = Mutex::new(());
@@ -151,3 +152,134 @@ fn test_mod2() {
     dbg!(&reports);
     assert!(reports.is_empty());
 }
+
+#[test]
+fn test_start_dates() {
+    let mut reports = check_mod_helper("mod3");
+
+    let titles = "history/titles/test_titles.txt";
+    let characters = "history/characters/test_characters.txt";
+
+    let report = take_report_contains(&mut reports, titles, "c_dead is held by 1, who is dead");
+    report.expect("dead holder test");
+
+    let report = take_report_contains(&mut reports, titles, "vassals and lieges form a cycle");
+    let report = report.expect("liege cycle test");
+    assert!(report.msg.ends_with(": 2 -> 3 -> 2"));
+
+    let report = take_report_contains(&mut reports, titles, "10 holds a");
+    assert!(report.is_none(), "liege of a lower title test");
+
+    let report = take_report_contains(&mut reports, characters, "4 is married to both 5 and 6");
+    report.expect("multiple spouses test");
+
+    let report = take_report_contains(&mut reports, characters, "7 is married to both");
+    assert!(report.is_none(), "divorce in the other spouse's history test");
+}
+
+const RELOAD_TRIGGERS: &str = "common/scripted_triggers/test_triggers.txt";
+
+/// Write a small mod to a temporary directory, for tests that change its files.
+fn write_reload_mod(name: &str) -> PathBuf {
+    let mod_root = std::env::temp_dir().join(format!("tiger-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&mod_root);
+    fs::create_dir_all(mod_root.join("common/scripted_triggers")).unwrap();
+    fs::create_dir_all(mod_root.join("events")).unwrap();
+    fs::write(mod_root.join(RELOAD_TRIGGERS), "test_trigger = { always = yes }\n").unwrap();
+    let events = "\u{feff}namespace = test_reload\n\n\
+        test_reload.1 = {\n\thidden = yes\n\ttrigger = { test_trigger = yes }\n}\n";
+    fs::write(mod_root.join("events/test_reload.txt"), events).unwrap();
+    mod_root
+}
+
+fn load_and_validate(mod_root: &Path) -> Everything {
+    let vanilla_dir = PathBuf::from("tests/files/ck3");
+    let mut everything = Everything::new(None, Some(&vanilla_dir), mod_root, Vec::new()).unwrap();
+    everything.load_all();
+    everything.validate_all();
+    everything
+}
+
+/// Reload the changed `file` and validate the changes, and return the resulting reports,
+/// as a list of strings for easy comparison.
+fn reload_and_validate(
+    everything: &mut Everything,
+    mut reports: Vec<LogReport>,
+    file: &Path,
+) -> Vec<String> {
+    let mut changes = Changes::default();
+    assert!(everything.reload_file(file, &mut changes));
+    if let Some(validated) = everything.validate_changes(&changes) {
+        reports
+            .retain(|report| !validated.iter().any(|path| path == report.primary().loc.fullpath()));
+    } else {
+        reports.clear();
+    }
+    reports.extend(take_reports());
+    sorted_reports(&reports)
+}
+
+/// Describe the reports in a way that doesn't depend on which `Everything` they came from.
+fn sorted_reports(reports: &[LogReport]) -> Vec<String> {
+    let mut reports: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            let pointers: Vec<_> = report
+                .pointers
+                .iter()
+                .map(|p| (p.loc.fullpath(), p.loc.line, p.loc.column, &p.msg))
+                .collect();
+            format!(
+                "{:?} {:?} {:?} {} {:?} {pointers:?}",
+                report.severity, report.confidence, report.key, report.msg, report.info
+            )
+        })
+        .collect();
+    reports.sort_unstable();
+    reports
+}
+
+#[test]
+fn test_reload_broken_file() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let mod_root = write_reload_mod("reload-broken");
+
+    let mut everything = load_and_validate(&mod_root);
+    let reports = take_reports();
+    assert!(!reports.iter().any(|report| report.msg.contains("test_trigger")));
+
+    let triggers = mod_root.join(RELOAD_TRIGGERS);
+    // Saved in the wrong encoding, so that the file can't be parsed at all.
+    fs::write(&triggers, b"test_trigger = { always = yes } # caf\xe9\n").unwrap();
+    let reports = reload_and_validate(&mut everything, reports, &triggers);
+    fs::remove_dir_all(&mod_root).unwrap();
+
+    // The trigger's old definition must not survive the reload.
+    assert!(
+        reports.iter().any(|report| report.contains("unknown token `test_trigger`")),
+        "{reports:#?}"
+    );
+}
+
+#[test]
+fn test_reload_same_as_full() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let mod_root = write_reload_mod("reload-full");
+
+    let mut everything = load_and_validate(&mod_root);
+    let reports = take_reports();
+
+    let triggers = mod_root.join(RELOAD_TRIGGERS);
+    fs::write(&triggers, "test_trigger_renamed = { always = yes }\n").unwrap();
+    let reloaded = reload_and_validate(&mut everything, reports, &triggers);
+
+    load_and_validate(&mod_root);
+    let full = sorted_reports(&take_reports());
+    fs::remove_dir_all(&mod_root).unwrap();
+
+    assert!(
+        reloaded.iter().any(|report| report.contains("unknown token `test_trigger`")),
+        "{reloaded:#?}"
+    );
+    assert_eq!(reloaded, full);
+}
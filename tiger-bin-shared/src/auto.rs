@@ -8,7 +8,7 @@ use console::Term;
 use tiger_lib::ModFile;
 #[cfg(feature = "vic3")]
 use tiger_lib::ModMetadata;
-use tiger_lib::{emit_reports, set_output_file, Everything};
+use tiger_lib::{emit_reports, set_output_file, set_supported_game_version, Everything};
 
 use crate::gamedir::{find_game_directory_steam, find_paradox_directory};
 use crate::GameConsts;
@@ -28,7 +28,7 @@ pub fn run(game_consts: &GameConsts) -> Result<()> {
     });
 
     eprintln!("This validator was made for {name} version {version}.");
-    eprintln!("If you are using a newer version of {name}, it may be inaccurate.");
+    set_supported_game_version(version);
     eprintln!("!! Currently it's inaccurate anyway because it's in beta state.");
 
     let game = find_game_directory_steam(app_id).context("Cannot find the game directory.")?;
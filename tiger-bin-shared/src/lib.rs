@@ -1,7 +1,10 @@
 mod auto;
 mod gamedir;
+mod lsp;
+mod session;
 mod tiger;
 mod update;
+mod watch;
 
 /// String constants associated with the game being verified.
 #[allow(missing_copy_implementations)]
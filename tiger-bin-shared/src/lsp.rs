@@ -0,0 +1,426 @@
+//! A Language Server Protocol server for live validation in editors.
+//!
+//! It keeps an [`Everything`] loaded, reloads files as they are edited, and publishes the reports
+//! as diagnostics. Only the small part of the protocol that is needed for that is implemented.
+
+use std::collections::{HashMap, HashSet};
+use std::env::current_dir;
+use std::fmt::Write as _;
+use std::fs::{read, read_to_string};
+use std::io::{stdin, stdout, BufRead, BufReader, Write};
+use std::mem::take;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+use tiger_lib::{set_file_override, take_reports, LogReport, PointedMessage, Severity};
+
+use crate::session::{absolute, report_path, Session};
+use crate::tiger::new_everything;
+use crate::GameConsts;
+
+/// How long to wait for more edits before validating again.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The BOM that most game script files start with. Editors don't include it in the document text.
+const BOM_CHAR: char = '\u{feff}';
+
+/// LSP error code for requests that the server doesn't handle.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Run the language server on stdin and stdout until the client tells it to exit.
+pub fn run(
+    game_consts: &GameConsts,
+    game: PathBuf,
+    modpath: PathBuf,
+    config: Option<PathBuf>,
+) -> Result<()> {
+    // The paths are turned into URIs for the client, so they have to be absolute.
+    let cwd = current_dir()?;
+    let mut server = Server {
+        source: format!("{}-tiger", game_consts.name_short.to_lowercase()),
+        game: cwd.join(game),
+        modpath: cwd.join(modpath),
+        config: config.map(|config| cwd.join(config)),
+        session: None,
+        open_docs: HashMap::new(),
+        dirty: HashSet::new(),
+        published: HashSet::new(),
+        initialized: false,
+        shutdown: false,
+    };
+
+    let (sender, receiver) = channel();
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stdin().lock());
+        loop {
+            match read_message(&mut reader) {
+                Ok(Some(msg)) => {
+                    if sender.send(msg).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("LSP read error: {e:#}");
+                    break;
+                }
+            }
+        }
+    });
+
+    loop {
+        let msg = if server.dirty.is_empty() {
+            match receiver.recv() {
+                Ok(msg) => msg,
+                Err(_) => break,
+            }
+        } else {
+            match receiver.recv_timeout(DEBOUNCE) {
+                Ok(msg) => msg,
+                Err(RecvTimeoutError::Timeout) => {
+                    server.revalidate();
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        };
+        if !server.handle(&msg) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+struct Server {
+    /// Name shown to the user as the source of the diagnostics.
+    source: String,
+    game: PathBuf,
+    modpath: PathBuf,
+    config: Option<PathBuf>,
+    /// `None` until the client has finished initializing, or if loading failed.
+    session: Option<Session>,
+    /// The texts of the documents that are open in the editor.
+    open_docs: HashMap<PathBuf, String>,
+    /// Files that changed since the last validation.
+    dirty: HashSet<PathBuf>,
+    /// Files that currently have diagnostics published, so that they can be cleared later.
+    published: HashSet<PathBuf>,
+    initialized: bool,
+    shutdown: bool,
+}
+
+impl Server {
+    /// Handle one message from the client. Returns false if the server should exit.
+    fn handle(&mut self, msg: &Value) -> bool {
+        let method = msg["method"].as_str().unwrap_or("");
+        let params = &msg["params"];
+        let id = msg.get("id");
+        match method {
+            "initialize" => {
+                let result = json!({
+                    "capabilities": {
+                        "textDocumentSync": {
+                            "openClose": true,
+                            // Full document sync
+                            "change": 1,
+                            "save": { "includeText": false },
+                        },
+                    },
+                    "serverInfo": { "name": self.source, "version": env!("CARGO_PKG_VERSION") },
+                });
+                respond(id, Ok(result));
+            }
+            "initialized" => {
+                self.initialized = true;
+                self.load_all();
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                respond(id, Ok(Value::Null));
+            }
+            "exit" => return false,
+            "textDocument/didOpen" => {
+                if let Some(path) = uri_to_path(&params["textDocument"]["uri"]) {
+                    let text = params["textDocument"]["text"].as_str().unwrap_or("").to_owned();
+                    let disk = read_to_string(&path).unwrap_or_default();
+                    if disk.strip_prefix(BOM_CHAR).unwrap_or(&disk) != text {
+                        set_text(&path, Some(text.clone()));
+                        self.dirty.insert(path.clone());
+                    }
+                    self.open_docs.insert(path, text);
+                }
+            }
+            "textDocument/didChange" => {
+                if let Some(path) = uri_to_path(&params["textDocument"]["uri"]) {
+                    // With full document sync, the last change holds the whole new text.
+                    let changes = params["contentChanges"].as_array();
+                    if let Some(text) = changes.and_then(|v| v.last()).map(|c| &c["text"]) {
+                        let text = text.as_str().unwrap_or("").to_owned();
+                        set_text(&path, Some(text.clone()));
+                        self.open_docs.insert(path.clone(), text);
+                        self.dirty.insert(path);
+                    }
+                }
+            }
+            "textDocument/didSave" => {
+                if let Some(path) = uri_to_path(&params["textDocument"]["uri"]) {
+                    self.dirty.insert(path);
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(path) = uri_to_path(&params["textDocument"]["uri"]) {
+                    self.open_docs.remove(&path);
+                    set_text(&path, None);
+                    self.dirty.insert(path);
+                }
+            }
+            _ => {
+                if id.is_some() {
+                    let msg = format!("method {method} not supported");
+                    respond(id, Err((METHOD_NOT_FOUND, msg)));
+                }
+            }
+        }
+        true
+    }
+
+    /// Load the game and mod from scratch, validate them, and publish the results.
+    fn load_all(&mut self) {
+        if !self.initialized || self.shutdown {
+            return;
+        }
+        self.dirty.clear();
+        // The old `Everything` has to be dropped before loading a new one, because dropping it
+        // resets global state.
+        self.session = None;
+        take_reports();
+        let everything =
+            match new_everything(self.config.as_deref(), Some(&self.game), self.modpath.clone()) {
+                Ok(everything) => everything,
+                Err(e) => {
+                    show_message(1, &format!("could not load mod: {e:#}"));
+                    return;
+                }
+            };
+        everything.load_output_settings(false);
+        everything.load_config_filtering_rules();
+        self.session = Some(Session::new(everything));
+        self.publish();
+    }
+
+    /// Reload the files that changed, validate what they affect, and publish the results.
+    fn revalidate(&mut self) {
+        if !self.initialized || self.shutdown {
+            return;
+        }
+        let dirty = take(&mut self.dirty);
+        if self.session.as_mut().is_some_and(|session| session.update(&dirty)) {
+            self.publish();
+        } else {
+            self.load_all();
+        }
+    }
+
+    /// Send the current load and validation reports to the client, and clear the diagnostics of
+    /// files that no longer have any.
+    fn publish(&mut self) {
+        let mut by_file: HashMap<PathBuf, Vec<Value>> = HashMap::new();
+        for report in self.session.iter().flat_map(Session::reports) {
+            by_file.entry(report_path(report)).or_default().push(self.diagnostic(report));
+        }
+        for path in take(&mut self.published) {
+            if !by_file.contains_key(&path) {
+                publish_diagnostics(&path, &[]);
+            }
+        }
+        for (path, diagnostics) in by_file {
+            publish_diagnostics(&path, &diagnostics);
+            self.published.insert(path);
+        }
+    }
+
+    fn diagnostic(&self, report: &LogReport) -> Value {
+        let severity = match report.severity {
+            Severity::Fatal | Severity::Error => 1,
+            Severity::Warning => 2,
+            Severity::Untidy => 3,
+            Severity::Tips => 4,
+        };
+        let mut message = report.msg.clone();
+        if let Some(info) = &report.info {
+            message.push('\n');
+            message.push_str(info);
+        }
+        let related: Vec<_> = report
+            .pointers
+            .iter()
+            .skip(1)
+            .map(|pointer| {
+                json!({
+                    "location": {
+                        "uri": path_to_uri(&absolute(pointer.loc.fullpath())),
+                        "range": self.range(pointer),
+                    },
+                    "message": pointer.msg.as_deref().unwrap_or("related location"),
+                })
+            })
+            .collect();
+        json!({
+            "range": self.range(report.primary()),
+            "severity": severity,
+            "code": report.key.to_string(),
+            "source": self.source,
+            "message": message,
+            "relatedInformation": related,
+        })
+    }
+
+    /// Convert the location of a pointer to an LSP range.
+    ///
+    /// The validator counts columns in characters, while LSP counts them in UTF-16 code units.
+    /// The conversion can only be done for documents that are open in the editor. For other
+    /// files the columns are passed on as-is, which is correct unless the line has characters
+    /// outside the Basic Multilingual Plane.
+    fn range(&self, pointer: &PointedMessage) -> Value {
+        if pointer.loc.line == 0 {
+            return json!({
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": 0, "character": 0 },
+            });
+        }
+        let line = pointer.loc.line as usize - 1;
+        let column = pointer.loc.column.saturating_sub(1) as usize;
+        let (start, end) = match self
+            .open_docs
+            .get(&absolute(pointer.loc.fullpath()))
+            .and_then(|text| text.strip_prefix(BOM_CHAR).unwrap_or(text).lines().nth(line))
+        {
+            Some(text) => {
+                let utf16_len =
+                    |chars: usize| -> usize { text.chars().take(chars).map(char::len_utf16).sum() };
+                (utf16_len(column), utf16_len(column + pointer.length))
+            }
+            None => (column, column + pointer.length),
+        };
+        json!({
+            "start": { "line": line, "character": start },
+            "end": { "line": line, "character": end },
+        })
+    }
+}
+
+/// Make the validator see `text` instead of the file contents on disk, or go back to the
+/// disk contents if `text` is `None`.
+fn set_text(path: &Path, text: Option<String>) {
+    let text = text.map(|text| {
+        // Restore the BOM that the editor hides, so that it doesn't look like it's missing.
+        let has_bom = read(path).is_ok_and(|bytes| bytes.starts_with(b"\xef\xbb\xbf"));
+        if has_bom && !text.starts_with(BOM_CHAR) {
+            format!("{BOM_CHAR}{text}")
+        } else {
+            text
+        }
+    });
+    set_file_override(path, text);
+}
+
+/// Read one message from the client. Returns `None` at the end of input.
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse::<usize>()?);
+        }
+    }
+    let Some(length) = length else {
+        bail!("message without Content-Length header");
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn send(msg: &Value) {
+    let body = msg.to_string();
+    let mut out = stdout().lock();
+    if let Err(e) = write!(out, "Content-Length: {}\r\n\r\n{body}", body.len()).and(out.flush()) {
+        eprintln!("LSP write error: {e:#}");
+    }
+}
+
+fn respond(id: Option<&Value>, result: std::result::Result<Value, (i64, String)>) {
+    let id = id.cloned().unwrap_or(Value::Null);
+    match result {
+        Ok(result) => send(&json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+        Err((code, message)) => send(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        })),
+    }
+}
+
+fn notify(method: &str, params: &Value) {
+    send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+}
+
+/// Show a message in the editor. `kind` is 1 for errors, 2 for warnings, 3 for info.
+fn show_message(kind: u8, message: &str) {
+    notify("window/showMessage", &json!({ "type": kind, "message": message }));
+}
+
+fn publish_diagnostics(path: &Path, diagnostics: &[Value]) {
+    let params = json!({ "uri": path_to_uri(path), "diagnostics": diagnostics });
+    notify("textDocument/publishDiagnostics", &params);
+}
+
+/// Convert an absolute path to a `file:` URI.
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    // Windows paths start with a drive letter instead of a slash.
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            _ = write!(uri, "%{byte:02X}");
+        }
+    }
+    uri
+}
+
+/// Convert a `file:` URI to a path. Returns `None` for other kinds of URI.
+fn uri_to_path(uri: &Value) -> Option<PathBuf> {
+    let rest = uri.as_str()?.strip_prefix("file://")?;
+    let mut bytes = Vec::new();
+    let mut iter = rest.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // Strip the slash in front of Windows drive letters.
+    if cfg!(windows) && path.as_bytes().get(2) == Some(&b':') {
+        return Some(PathBuf::from(&path[1..]));
+    }
+    Some(PathBuf::from(path))
+}
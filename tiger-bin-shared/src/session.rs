@@ -0,0 +1,78 @@
+//! Keeps a mod loaded between validations, for the `lsp` subcommand and the `--watch` option.
+
+use std::collections::HashSet;
+use std::env::current_dir;
+use std::path::{Path, PathBuf};
+
+use tiger_lib::{take_reports, warn_unused_ignores, Changes, Everything, LogReport};
+
+pub(crate) struct Session {
+    pub(crate) everything: Everything,
+    /// Reports emitted while loading the files. Loading is not repeated for every validation,
+    /// so these are kept and updated per file.
+    load_reports: Vec<LogReport>,
+    /// Reports emitted by the last validation. Validation after a change is often limited to the
+    /// affected files, and then only the reports for those files are replaced.
+    reports: Vec<LogReport>,
+}
+
+impl Session {
+    /// Load and validate everything. The output settings and filtering rules should already have
+    /// been applied, and any earlier reports taken.
+    pub(crate) fn new(mut everything: Everything) -> Self {
+        everything.load_all();
+        everything.check_rivers();
+        let load_reports = take_reports();
+        everything.validate_all();
+        warn_unused_ignores();
+        let reports = take_reports();
+        Session { everything, load_reports, reports }
+    }
+
+    /// Reload the `changed` files and validate again what they may affect.
+    /// The paths must be absolute.
+    ///
+    /// Returns false if the files can't be reloaded individually. The session is then no longer
+    /// usable and a new one should be created.
+    pub(crate) fn update(&mut self, changed: &HashSet<PathBuf>) -> bool {
+        let mut changes = Changes::default();
+        if !changed.iter().all(|path| self.everything.reload_file(path, &mut changes)) {
+            return false;
+        }
+        let reports = take_reports();
+        self.load_reports.retain(|report| !changed.contains(&report_path(report)));
+        self.load_reports.extend(reports.into_iter().filter(|r| changed.contains(&report_path(r))));
+        let validated = self.everything.validate_changes(&changes);
+        warn_unused_ignores();
+        if let Some(validated) = validated {
+            let validated: HashSet<PathBuf> = validated.iter().map(|path| absolute(path)).collect();
+            self.reports.retain(|report| !validated.contains(&report_path(report)));
+            // Reports about other files may have been emitted again, such as ones about macro
+            // expansions in scripted effects.
+            let mut seen: HashSet<LogReport> = self.reports.drain(..).collect();
+            seen.extend(take_reports());
+            self.reports = seen.into_iter().collect();
+        } else {
+            self.reports = take_reports();
+        }
+        true
+    }
+
+    /// Iterate over the current reports from both loading and validation.
+    pub(crate) fn reports(&self) -> impl Iterator<Item = &LogReport> {
+        self.load_reports.iter().chain(&self.reports)
+    }
+}
+
+/// The absolute path of the file that the report is primarily about.
+pub(crate) fn report_path(report: &LogReport) -> PathBuf {
+    absolute(report.primary().loc.fullpath())
+}
+
+pub(crate) fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        current_dir().map_or_else(|_| path.to_path_buf(), |cwd| cwd.join(path))
+    }
+}
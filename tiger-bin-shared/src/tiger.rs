@@ -1,4 +1,5 @@
-use std::{mem::forget, path::PathBuf};
+use std::mem::forget;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
 use clap::{Args, Parser, Subcommand};
@@ -7,12 +8,16 @@ use tiger_lib::ModFile;
 #[cfg(feature = "vic3")]
 use tiger_lib::ModMetadata;
 use tiger_lib::{
-    disable_ansi_colors, emit_reports, set_show_loaded_mods, set_show_vanilla, suppress_from_json,
-    validate_config_file, Everything,
+    apply_fixes, baseline_from_json, baseline_stale_entries, disable_ansi_colors, emit_report_list,
+    emit_reports, load_script_docs, set_output_sarif, set_show_loaded_mods, set_show_vanilla,
+    set_supported_game_version, suppress_from_json, take_reports, validate_config_file,
+    warn_unused_ignores, write_baseline_json, Everything,
 };
 
 use crate::gamedir::find_game_directory_steam;
+use crate::lsp::run as lsp;
 use crate::update::update;
+use crate::watch::watch;
 use crate::GameConsts;
 
 #[derive(Parser)]
@@ -35,6 +40,28 @@ enum Commands {
         /// release version (e.g. 0.9.3)
         version: Option<String>,
     },
+    /// Run as a Language Server Protocol server on stdin and stdout, for live validation in
+    /// editors.
+    Lsp(LspArgs),
+}
+
+#[derive(Args)]
+struct LspArgs {
+    #[cfg(feature = "vic3")]
+    /// Path to folder of mod to check.
+    modpath: PathBuf,
+    #[cfg(any(feature = "ck3", feature = "imperator"))]
+    /// Path to .mod file of mod to check.
+    modpath: PathBuf,
+    #[cfg_attr(feature = "ck3", clap(visible_alias = "ck3"))]
+    #[cfg_attr(feature = "vic3", clap(visible_alias = "vic3"))]
+    #[cfg_attr(feature = "imperator", clap(visible_alias = "imperator"))]
+    #[clap(long)]
+    /// Path to game main directory.
+    game: Option<PathBuf>,
+    /// Path to custom .conf file.
+    #[clap(long)]
+    config: Option<PathBuf>,
 }
 
 #[derive(Args)]
@@ -63,7 +90,13 @@ struct ValidateArgs {
     /// Output the reports in JSON format
     #[clap(long)]
     json: bool,
-    /// Warn about items that are defined but unused
+    /// Output the reports in SARIF format, for code scanning tools
+    #[clap(long, conflicts_with = "json")]
+    sarif: bool,
+    /// Warn about localization, files and items that are defined but unused.
+    /// The items checked are only the kinds that do nothing unless referred to: events,
+    /// on-actions, scripted effects, triggers, lists, modifiers and values, effect and trigger
+    /// localizations, and gui types and templates.
     #[clap(long)]
     unused: bool,
     /// Do checks specific to the Princes of Darkness mod
@@ -77,6 +110,51 @@ struct ValidateArgs {
     /// Load a JSON file of reports to remove from the output.
     #[clap(long)]
     suppress: Option<PathBuf>,
+    /// Write the reports to a baseline file instead of printing them.
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["baseline", "watch"])]
+    baseline_write: Option<PathBuf>,
+    /// Only show reports that are not in this baseline file.
+    #[clap(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+    /// Write a JSON index of where items are defined and referred to.
+    #[clap(long, value_name = "FILE", conflicts_with = "watch")]
+    references: Option<PathBuf>,
+    /// Write the graph of which events and on-actions trigger which others.
+    /// The format is Graphviz DOT if FILE ends in .dot or .gv, and JSON otherwise.
+    #[clap(long, value_name = "FILE", conflicts_with = "watch")]
+    event_graph: Option<PathBuf>,
+    /// Write a table of which localization keys are missing, stale, or identical to English in
+    /// each language, per file. The format is CSV if FILE ends in .csv, and JSON otherwise.
+    #[clap(long, value_name = "FILE", conflicts_with = "watch")]
+    loca_report: Option<PathBuf>,
+    /// Save the mod's localization texts in all languages, for use with `--loca-snapshot`.
+    #[clap(long, value_name = "FILE", conflicts_with = "watch")]
+    loca_snapshot_write: Option<PathBuf>,
+    /// Report translations that were not updated after the English text changed since this
+    /// snapshot was written.
+    #[clap(long, value_name = "FILE", conflicts_with = "watch")]
+    loca_snapshot: Option<PathBuf>,
+    /// Apply the fixes that some reports offer, such as renaming replaced fields or adding a
+    /// missing BOM. Only the mod's own files are changed.
+    #[clap(long, conflicts_with_all = ["watch", "baseline_write"])]
+    fix: bool,
+    /// With `--fix`, print the fixes as a unified diff instead of changing the files.
+    #[clap(long, requires = "fix")]
+    dry_run: bool,
+    /// Read the definitions of effects, triggers, event targets, on-actions, and datafunctions
+    /// from the logs that the game writes with the `script_docs` console command.
+    /// Point this at the game's `logs/script_docs` directory.
+    #[clap(long, value_name = "DIR")]
+    script_docs: Option<PathBuf>,
+    /// Use only the definitions from the `--script-docs` logs, instead of merging them with the
+    /// built-in ones.
+    #[clap(long, requires = "script_docs")]
+    script_docs_replace: bool,
+    /// Keep running, and validate again whenever files in the mod change.
+    /// Only the reports that are new or resolved are printed then.
+    #[clap(long, conflicts_with = "unused")]
+    #[cfg_attr(feature = "ck3", clap(conflicts_with = "pod"))]
+    watch: bool,
 }
 
 /// Run the main tiger application.
@@ -84,8 +162,9 @@ struct ValidateArgs {
 /// It provides a number of command line arguments, as well as self-updating capability with the `update` subcommand.
 #[allow(clippy::missing_panics_doc)] // it thinks we can panic on cli.validate_args.unwrap()
 pub fn run(game_consts: &GameConsts, current_version: &str) -> Result<()> {
-    let &GameConsts { name, name_short, version, app_id, signature_file, .. } = game_consts;
+    let &GameConsts { name, version, .. } = game_consts;
     let cli = Cli::parse();
+    set_supported_game_version(version);
 
     #[allow(clippy::single_match_else)]
     match cli.command {
@@ -93,6 +172,11 @@ pub fn run(game_consts: &GameConsts, current_version: &str) -> Result<()> {
             update(current_version, target_version.as_deref())?;
             Ok(())
         }
+        Some(Commands::Lsp(args)) => {
+            let game = find_game_dir(args.game, game_consts)?;
+            let config = validate_config_file(args.config);
+            lsp(game_consts, game, args.modpath, config)
+        }
         None => {
             let mut args = cli.validate_args.unwrap();
             #[cfg(windows)]
@@ -102,37 +186,31 @@ pub fn run(game_consts: &GameConsts, current_version: &str) -> Result<()> {
             }
 
             eprintln!("This validator was made for {name} version {version}.");
-            eprintln!("If you are using a newer version of {name}, it may be inaccurate.");
             eprintln!("!! Currently it's inaccurate anyway because it's in beta state.");
 
-            if args.game.is_none() {
-                args.game = find_game_directory_steam(app_id).ok();
-            }
-            if let Some(ref mut game) = args.game {
-                eprintln!("Using {name_short} directory: {}", game.display());
-                let mut sig = game.clone();
-                sig.push(signature_file);
-                if !sig.is_file() {
-                    eprintln!("That does not look like a {name_short} directory.");
-                    game.push("..");
-                    eprintln!("Trying: {}", game.display());
-                    sig.clone_from(game);
-                    sig.push(signature_file);
-                    if sig.is_file() {
-                        eprintln!("Ok.");
-                    } else {
-                        bail!("Cannot find {name_short} directory. Please supply it as the --game option.");
-                    }
-                }
-            } else {
-                bail!("Cannot find {name_short} directory. Please supply it as the --game option.");
-            }
+            args.game = Some(find_game_dir(args.game, game_consts)?);
 
             args.config = validate_config_file(args.config);
 
-            if let Some(suppress) = args.suppress {
+            if let Some(suppress) = &args.suppress {
                 eprintln!("Suppressing reports from: {}", suppress.display());
-                suppress_from_json(&suppress)?;
+                suppress_from_json(suppress)?;
+            }
+
+            if let Some(baseline) = &args.baseline {
+                eprintln!(
+                    "Showing only reports that are not in the baseline: {}",
+                    baseline.display()
+                );
+                baseline_from_json(baseline)?;
+            }
+
+            if let Some(script_docs) = &args.script_docs {
+                let count = load_script_docs(script_docs, args.script_docs_replace)?;
+                eprintln!(
+                    "Loaded {count} definitions from the game logs in {}",
+                    script_docs.display()
+                );
             }
 
             if args.show_vanilla {
@@ -145,7 +223,7 @@ pub fn run(game_consts: &GameConsts, current_version: &str) -> Result<()> {
 
             if args.unused {
                 eprintln!(
-                    "Showing warnings for unused localization. There will be many false positives."
+                    "Showing warnings for unused localization, files, and items. There will be many false positives."
                 );
             }
 
@@ -159,81 +237,187 @@ pub fn run(game_consts: &GameConsts, current_version: &str) -> Result<()> {
                 disable_ansi_colors();
             }
 
-            let mut everything;
-
-            #[cfg(any(feature = "ck3", feature = "imperator"))]
-            {
-                if args.modpath.is_dir() {
-                    args.modpath.push("descriptor.mod");
-                }
-
-                let modfile = ModFile::read(&args.modpath)?;
-                let modpath = modfile.modpath();
-                if !modpath.exists() {
-                    eprintln!("Looking for mod in {}", modpath.display());
-                    bail!("Cannot find mod directory. Please make sure the .mod file is correct.");
-                }
-                eprintln!("Using mod directory: {}", modpath.display());
-
-                everything = Everything::new(
-                    args.config.as_deref(),
-                    args.game.as_deref(),
-                    &modpath,
-                    modfile.replace_paths(),
-                )?;
-            }
-            #[cfg(feature = "vic3")]
-            {
-                let metadata = ModMetadata::read(&args.modpath)?;
-                eprintln!("Using mod directory: {}", metadata.modpath().display());
-
-                everything = Everything::new(
-                    args.config.as_deref(),
-                    args.game.as_deref(),
-                    &args.modpath,
-                    metadata.replace_paths(),
-                )?;
-            }
+            let mut everything =
+                new_everything(args.config.as_deref(), args.game.as_deref(), args.modpath.clone())?;
 
             // Print a blank line between the preamble and the first report:
             eprintln!();
 
-            everything.load_output_settings(true);
-            everything.load_config_filtering_rules();
+            apply_settings(&everything, &args);
 
-            if !args.json {
-                emit_reports(false);
+            if args.watch {
+                return watch(everything, args.json, || {
+                    let everything = new_everything(
+                        args.config.as_deref(),
+                        args.game.as_deref(),
+                        args.modpath.clone(),
+                    )?;
+                    apply_settings(&everything, &args);
+                    Ok(everything)
+                });
             }
 
-            // We must apply the --no-color flag AFTER loading and applying the config,
-            // because we want it to override the config.
-            if args.no_color {
-                disable_ansi_colors();
+            if args.unused {
+                everything.track_unused();
             }
-            // Same logic applies to showing vanilla and other mods
-            if args.show_vanilla {
-                set_show_vanilla(true);
+            if args.references.is_some() {
+                everything.record_references();
             }
-            if args.show_mods {
-                set_show_loaded_mods(true);
+            if args.event_graph.is_some() {
+                everything.record_event_graph();
             }
-
             everything.load_all();
             everything.validate_all();
             everything.check_rivers();
+            if let Some(references) = &args.references {
+                everything.write_references_json(references)?;
+                eprintln!("Wrote the references index: {}", references.display());
+            }
+            if let Some(event_graph) = &args.event_graph {
+                everything.write_event_graph(event_graph)?;
+                eprintln!("Wrote the event graph: {}", event_graph.display());
+            }
+            if let Some(snapshot) = &args.loca_snapshot {
+                let changed = everything.check_loca_snapshot(snapshot)?;
+                eprintln!(
+                    "{changed} English localization texts changed since the snapshot: {}",
+                    snapshot.display()
+                );
+            }
+            if let Some(snapshot) = &args.loca_snapshot_write {
+                let count = everything.write_loca_snapshot(snapshot)?;
+                eprintln!(
+                    "Wrote {count} localization keys to the snapshot: {}",
+                    snapshot.display()
+                );
+            }
+            if let Some(loca_report) = &args.loca_report {
+                everything.write_loca_report(loca_report)?;
+                eprintln!("Wrote the localization report: {}", loca_report.display());
+            }
 
             #[cfg(feature = "ck3")]
             if args.pod {
                 everything.check_pod();
             }
-            emit_reports(args.json);
             if args.unused {
                 everything.check_unused();
             }
-
+            warn_unused_ignores();
+            if let Some(baseline) = &args.baseline_write {
+                let count = write_baseline_json(baseline)?;
+                eprintln!("Wrote {count} reports to the baseline: {}", baseline.display());
+            } else if args.fix {
+                let mut reports = take_reports();
+                let summary = apply_fixes(&mut reports, args.dry_run)?;
+                if args.dry_run {
+                    // Only the diff goes to stdout, so that it can be saved and applied as a patch.
+                    eprintln!(
+                        "{} fixes would be applied to {} files.",
+                        summary.applied, summary.files
+                    );
+                } else {
+                    eprintln!("Applied {} fixes to {} files.", summary.applied, summary.files);
+                    emit_report_list(&reports, args.json);
+                }
+                if summary.skipped > 0 {
+                    eprintln!("{} fixes were skipped because they overlapped with others or did not fit the file.", summary.skipped);
+                }
+            } else {
+                emit_reports(args.json);
+            }
+            if let Some(stale) = baseline_stale_entries() {
+                if stale > 0 {
+                    eprintln!("{stale} reports in the baseline no longer occur.");
+                }
+            }
             // Properly dropping `everything` takes a noticeable amount of time, and we're exiting anyway.
             forget(everything);
             Ok(())
         }
     }
 }
+
+/// Load the output settings and filtering rules from the config file, and then apply the command
+/// line options that override them.
+fn apply_settings(everything: &Everything, args: &ValidateArgs) {
+    everything.load_output_settings(true);
+    everything.load_config_filtering_rules();
+
+    // We must apply the --no-color flag AFTER loading and applying the config,
+    // because we want it to override the config.
+    if args.no_color {
+        disable_ansi_colors();
+    }
+    // Same logic applies to showing vanilla and other mods
+    if args.show_vanilla {
+        set_show_vanilla(true);
+    }
+    if args.show_mods {
+        set_show_loaded_mods(true);
+    }
+
+    // Print the reports from loading the mod and the game, such as the game version check.
+    if args.sarif {
+        set_output_sarif(true);
+    } else if !args.json {
+        emit_reports(false);
+    }
+}
+
+/// Locate the game directory, either from the `--game` option or from the Steam library, and
+/// check that it looks like the right game.
+fn find_game_dir(game: Option<PathBuf>, game_consts: &GameConsts) -> Result<PathBuf> {
+    let &GameConsts { name_short, app_id, signature_file, .. } = game_consts;
+    let Some(mut game) = game.or_else(|| find_game_directory_steam(app_id).ok()) else {
+        bail!("Cannot find {name_short} directory. Please supply it as the --game option.");
+    };
+    eprintln!("Using {name_short} directory: {}", game.display());
+    let mut sig = game.clone();
+    sig.push(signature_file);
+    if !sig.is_file() {
+        eprintln!("That does not look like a {name_short} directory.");
+        game.push("..");
+        eprintln!("Trying: {}", game.display());
+        sig.clone_from(&game);
+        sig.push(signature_file);
+        if sig.is_file() {
+            eprintln!("Ok.");
+        } else {
+            bail!("Cannot find {name_short} directory. Please supply it as the --game option.");
+        }
+    }
+    Ok(game)
+}
+
+/// Read the mod's `.mod` file or metadata, and create an [`Everything`] for it.
+/// The files are scanned but not yet loaded.
+pub(crate) fn new_everything(
+    config: Option<&Path>,
+    game: Option<&Path>,
+    #[allow(unused_mut)] mut modpath: PathBuf,
+) -> Result<Everything> {
+    #[cfg(any(feature = "ck3", feature = "imperator"))]
+    {
+        if modpath.is_dir() {
+            modpath.push("descriptor.mod");
+        }
+
+        let modfile = ModFile::read(&modpath)?;
+        let modpath = modfile.modpath();
+        if !modpath.exists() {
+            eprintln!("Looking for mod in {}", modpath.display());
+            bail!("Cannot find mod directory. Please make sure the .mod file is correct.");
+        }
+        eprintln!("Using mod directory: {}", modpath.display());
+
+        Everything::new(config, game, &modpath, modfile.replace_paths())
+    }
+    #[cfg(feature = "vic3")]
+    {
+        let metadata = ModMetadata::read(&modpath)?;
+        eprintln!("Using mod directory: {}", metadata.modpath().display());
+
+        Everything::new(config, game, &modpath, metadata.replace_paths())
+    }
+}
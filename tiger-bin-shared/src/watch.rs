@@ -0,0 +1,208 @@
+//! The `--watch` option: keep running and validate again whenever files in the mod change.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use anyhow::Result;
+use notify::event::ModifyKind;
+use notify::{recommended_watcher, EventKind, RecursiveMode, Watcher};
+use tiger_lib::{
+    emit_report_list, report_fingerprint, take_reports, Everything, Fingerprint, LogReport,
+};
+
+use crate::session::{absolute, Session};
+
+/// How long to wait for more changes before validating again. Editors often write a file in
+/// several steps.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Validate `everything` and print the reports, then watch the mod directories and print which
+/// reports are new or resolved after every change.
+///
+/// `reload` is called to create a fresh [`Everything`] when files were added or removed, since
+/// those changes can't be handled by reloading individual files.
+///
+/// Reports are compared by their baseline [`Fingerprint`], so that a report that only moved
+/// because lines were added or removed above it is neither new nor resolved. Line numbers are
+/// only used to print the reports.
+pub(crate) fn watch<F>(everything: Everything, json: bool, reload: F) -> Result<()>
+where
+    F: Fn() -> Result<Everything>,
+{
+    let roots: Vec<PathBuf> = everything.mod_roots().into_iter().map(absolute).collect();
+
+    let mut session = Some(Session::new(everything));
+    let mut current = collect(session.as_ref());
+    emit_report_list(&sorted(current.iter().map(|(_, report)| report)), json);
+
+    let (sender, receiver) = channel();
+    let mut watcher = recommended_watcher(sender)?;
+    for root in &roots {
+        watcher.watch(root, RecursiveMode::Recursive)?;
+        eprintln!("Watching {} for changes.", root.display());
+    }
+
+    loop {
+        let mut changed = HashSet::new();
+        let mut removed = false;
+        // Block until something happens, then collect events until things are quiet again.
+        let Ok(mut event) = receiver.recv() else {
+            return Ok(());
+        };
+        loop {
+            match event {
+                Ok(event) => match event.kind {
+                    EventKind::Create(_) | EventKind::Remove(_) => {
+                        classify(event.paths, &roots, &mut changed, &mut removed);
+                    }
+                    EventKind::Modify(kind) if !matches!(kind, ModifyKind::Metadata(_)) => {
+                        classify(event.paths, &roots, &mut changed, &mut removed);
+                    }
+                    _ => (),
+                },
+                Err(e) => eprintln!("Error while watching files: {e}"),
+            }
+            event = match receiver.recv_timeout(DEBOUNCE) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            };
+        }
+        if changed.is_empty() && !removed {
+            continue;
+        }
+
+        eprintln!("\nFiles changed, validating again...");
+        // Files that are new since loading are not handled by `update`, so it will return false.
+        let updated = !removed && session.as_mut().is_some_and(|s| s.update(&changed));
+        if !updated {
+            // The old `Everything` has to be dropped before loading a new one, because dropping
+            // it resets global state.
+            session = None;
+            take_reports();
+            match reload() {
+                Ok(everything) => session = Some(Session::new(everything)),
+                Err(e) => {
+                    eprintln!("Could not load the mod: {e:#}");
+                    continue;
+                }
+            }
+        }
+
+        let latest = collect(session.as_ref());
+        let new = sorted(unmatched(&latest, &current));
+        let resolved = sorted(unmatched(&current, &latest));
+        if new.is_empty() && resolved.is_empty() {
+            eprintln!("No changes in the reports ({} in total).", latest.len());
+        } else {
+            eprintln!(
+                "{} new, {} resolved ({} in total).",
+                new.len(),
+                resolved.len(),
+                latest.len()
+            );
+        }
+        if !resolved.is_empty() {
+            eprintln!("\nResolved:");
+            if json {
+                emit_report_list(&resolved, json);
+            } else {
+                // The full report would quote the file as it is now, which may not match anymore.
+                for report in &resolved {
+                    let loc = report.primary().loc;
+                    eprintln!(
+                        "  {}:{}: ({}) {}",
+                        loc.pathname().display(),
+                        loc.line,
+                        report.key,
+                        report.msg
+                    );
+                }
+            }
+        }
+        if !new.is_empty() {
+            eprintln!("\nNew:");
+            emit_report_list(&new, json);
+        }
+        current = latest;
+    }
+}
+
+/// Collect the session's current reports with their fingerprints.
+fn collect(session: Option<&Session>) -> Vec<(Fingerprint, LogReport)> {
+    session
+        .into_iter()
+        .flat_map(Session::reports)
+        .map(|report| (report_fingerprint(report), report.clone()))
+        .collect()
+}
+
+/// Return the `reports` that are not matched by any of the `others`. Each of the `others` matches
+/// only one report, so that a second occurrence of the same problem still counts as new.
+fn unmatched<'a>(
+    reports: &'a [(Fingerprint, LogReport)],
+    others: &'a [(Fingerprint, LogReport)],
+) -> impl Iterator<Item = &'a LogReport> {
+    let mut counts: HashMap<&Fingerprint, usize> = HashMap::new();
+    for (fingerprint, _) in others {
+        *counts.entry(fingerprint).or_default() += 1;
+    }
+    reports.iter().filter_map(move |(fingerprint, report)| match counts.get_mut(fingerprint) {
+        Some(count) if *count > 0 => {
+            *count -= 1;
+            None
+        }
+        _ => Some(report),
+    })
+}
+
+/// Sort the paths of a filesystem event into files that were changed or created, and whether
+/// any were removed. Directories are ignored; the files in them get their own events.
+fn classify(
+    paths: Vec<PathBuf>,
+    roots: &[PathBuf],
+    changed: &mut HashSet<PathBuf>,
+    removed: &mut bool,
+) {
+    for path in paths {
+        if is_hidden(&path, roots) {
+            continue;
+        }
+        if path.is_file() {
+            changed.insert(path);
+        } else if !path.exists() {
+            *removed = true;
+        }
+    }
+}
+
+/// Return true iff the path is hidden within one of the `roots`, or is inside a hidden directory
+/// there. Such files are ignored, because they are things like `.git` or editors' temporary files.
+fn is_hidden(path: &Path, roots: &[PathBuf]) -> bool {
+    roots
+        .iter()
+        .filter_map(|root| path.strip_prefix(root).ok())
+        .any(|inner| inner.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')))
+}
+
+/// Return the reports in the order in which they are normally printed.
+fn sorted<'a>(reports: impl Iterator<Item = &'a LogReport>) -> Vec<LogReport> {
+    let mut reports: Vec<LogReport> = reports.cloned().collect();
+    reports.sort_unstable_by(|a, b| {
+        let mut cmp = b.severity.cmp(&a.severity);
+        if cmp == Ordering::Equal {
+            cmp = b.confidence.cmp(&a.confidence);
+        }
+        if cmp == Ordering::Equal {
+            cmp = a.primary().loc.cmp(&b.primary().loc);
+        }
+        if cmp == Ordering::Equal {
+            cmp = a.msg.cmp(&b.msg);
+        }
+        cmp
+    });
+    reports
+}
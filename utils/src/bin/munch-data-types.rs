@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 use anyhow::{bail, Context, Result};
 use clap::{Parser, ValueEnum};
 use strum_macros::Display;
+use tiger_lib::{data_types_entries, DataTypesEntry, DefinitionType};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum, Display)]
 enum Game {
@@ -281,7 +282,7 @@ fn write_nonglobals(mut nonglobals: HashMap<String, NonGlobal>, fname: PathBuf)
 }
 
 fn parse_datafunction(
-    item: &str,
+    entry: DataTypesEntry,
     types: &mut HashSet<String>,
     global_promotes: &mut HashMap<String, Global>,
     global_functions: &mut HashMap<String, Global>,
@@ -289,84 +290,37 @@ fn parse_datafunction(
     functions: &mut HashMap<String, NonGlobal>,
     game: Game,
 ) {
-    if item.is_empty() {
+    let name = entry.name.to_owned();
+    if entry.definition == DefinitionType::Type {
+        types.insert(name);
         return;
     }
-    let header = item.lines().next().unwrap();
 
-    if item.contains("Definition type: Type") {
-        types.insert(header.to_owned());
-        return;
-    }
-
-    if item.contains("Definition type: Global macro") {
-        return;
-    }
-
-    let mut name = header;
-    let mut nargs = 0;
-    if let Some((s1, s2)) = header.split_once('(') {
-        name = s1;
-        nargs = s2.split(',').count();
-    }
-    let mut args = Vec::new();
-    for _ in 0..nargs {
-        args.push("DType(Unknown)".to_string());
-    }
-
-    let mut rtype = "";
-    if let Some((_, s2)) = item.split_once("Return type: ") {
-        rtype = s2.trim();
-    }
-    if rtype == "[unregistered]" {
-        rtype = "Unknown";
-    } else if rtype == "_null_type_" {
-        rtype = "void";
-    }
-    // Implement bugfixes to the game's logs
-    if name == "IntToFixedPoint" && rtype == "float" {
-        rtype = "CFixedPoint";
-    } else if name == "IntToUnsigned" && rtype == "float" {
-        rtype = "uint32";
-    }
-    let store;
-    if !GENERIC_TYPES.contains(&rtype) {
-        store = format!("{game}({rtype})");
-        rtype = &store;
-    }
-
-    if item.contains("Definition type: Global promote") {
-        global_promotes
-            .insert(name.to_owned(), Global::new(name.to_owned(), args, rtype.to_owned()));
-        return;
-    } else if item.contains("Definition type: Global function") {
-        global_functions
-            .insert(name.to_owned(), Global::new(name.to_owned(), args, rtype.to_owned()));
-        return;
-    }
-
-    let (mut dtype, barename) = name.split_once('.').unwrap();
-    let store2;
-    if !GENERIC_TYPES.contains(&dtype) {
-        store2 = format!("{game}({dtype})");
-        dtype = &store2;
-    }
-    if barename == "Self" || barename == "AccessSelf" {
-        rtype = dtype;
-    }
-
-    if item.contains("Definition type: Promote") {
-        promotes.insert(
-            name.to_owned(),
-            NonGlobal::new(barename.to_owned(), dtype.to_owned(), args, rtype.to_owned()),
-        );
+    let args = vec!["DType(Unknown)".to_string(); entry.nargs];
+    let wrap = |dtype: &str| {
+        if GENERIC_TYPES.contains(&dtype) {
+            dtype.to_owned()
+        } else {
+            format!("{game}({dtype})")
+        }
+    };
+    let rtype = wrap(entry.rtype);
+
+    let Some(dtype) = entry.dtype else {
+        let global = Global::new(name.clone(), args, rtype);
+        if entry.definition == DefinitionType::GlobalPromote {
+            global_promotes.insert(name, global);
+        } else {
+            global_functions.insert(name, global);
+        }
         return;
-    }
-    if item.contains("Definition type: Function") {
-        functions.insert(
-            name.to_owned(),
-            NonGlobal::new(barename.to_owned(), dtype.to_owned(), args, rtype.to_owned()),
-        );
+    };
+    let key = format!("{dtype}.{name}");
+    let nonglobal = NonGlobal::new(name, wrap(dtype), args, rtype);
+    if entry.definition == DefinitionType::Promote {
+        promotes.insert(key, nonglobal);
+    } else {
+        functions.insert(key, nonglobal);
     }
 }
 
@@ -396,9 +350,9 @@ fn main() -> Result<()> {
             continue;
         }
         let content = read_to_string(entry.path())?;
-        for item in content.split("\n-----------------------\n\n") {
+        for entry in data_types_entries(&content) {
             parse_datafunction(
-                item,
+                entry,
                 &mut new_types,
                 &mut new_global_promotes,
                 &mut new_global_functions,